@@ -1,5 +1,13 @@
 use derive_syn_parse::Parse;
-use syn::{parse::ParseStream, Expr, Ident, Token, Type};
+use proc_macro_error::emit_error;
+use syn::{
+    ext::IdentExt,
+    parse::ParseStream,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token::{Comma, Paren},
+    Attribute, Expr, Ident, LitStr, Member, Token, Type, Visibility,
+};
 
 fn ident_is(s: &str) -> impl Fn(ParseStream) -> bool + '_ {
     move |parse_stream| match parse_stream.fork().parse::<Ident>() {
@@ -8,7 +16,134 @@ fn ident_is(s: &str) -> impl Fn(ParseStream) -> bool + '_ {
     }
 }
 
-/// Type of expression users can add
+/// Options which can be given on fields
+const FIELD_OPTIONS: &[&str] = &[
+    "ignore",
+    "ty",
+    "converter",
+    "try_converter",
+    "default",
+    "into",
+    "parse",
+];
+
+/// Options which can be given on structs and enums
+const CONTAINER_OPTIONS: &[&str] = &[
+    "error",
+    "builder",
+    "vis",
+    "name",
+    "const",
+    "constructor",
+    "alias",
+    "into",
+    "doc",
+];
+
+/// Options which can be given inside `constructor(...)`
+const CONSTRUCTOR_OPTIONS: &[&str] = &["name", "vis", "const", "defaults"];
+
+/// Where each set of options can be given
+const LEVELS: &[(&[&str], &str)] = &[
+    (FIELD_OPTIONS, "on fields"),
+    (CONTAINER_OPTIONS, "on structs and enums"),
+    (CONSTRUCTOR_OPTIONS, "inside `constructor(...)`"),
+];
+
+/// Check the next option is one of `options` before parsing it,
+/// so we can say where an option belongs, or what the user might have meant
+fn check_option(input: ParseStream, options: &[&str]) -> syn::Result<()> {
+    let ident = match input.fork().call(Ident::parse_any) {
+        Ok(ident) => ident,
+        Err(_) => return Ok(()),
+    };
+    let name = ident.to_string();
+    if options.contains(&name.as_str()) {
+        return Ok(());
+    }
+    let here = LEVELS
+        .iter()
+        .find(|(level, _)| *level == options)
+        .map(|(_, place)| *place)
+        .unwrap_or_default();
+    if let Some((_, place)) = LEVELS
+        .iter()
+        .find(|(level, _)| level.contains(&name.as_str()))
+    {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("`{name}` can't be used {here}, only {place}"),
+        ));
+    }
+    let suggestion = options
+        .iter()
+        .map(|option| (edit_distance(&name, option), option))
+        .filter(|(distance, option)| *distance <= option.len() / 3 + 1)
+        .min()
+        .map(|(_, option)| format!(", did you mean `{option}`?"))
+        .unwrap_or_else(|| String::from("."));
+    let (last, rest) = options.split_last().expect("there are always options");
+    let expected = rest
+        .iter()
+        .map(|option| format!("`{option}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(syn::Error::new(
+        ident.span(),
+        format!("unknown option `{name}`{suggestion} Expected one of {expected} or `{last}`"),
+    ))
+}
+
+/// How many single character insertions, deletions or substitutions turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Get the `#[generic_new(...)]` from `attrs`, if there is one
+pub fn generic_new_attribute(attrs: &[Attribute], item: impl Spanned) -> Option<&Attribute> {
+    match attrs
+        .iter()
+        .filter(|attr| {
+            attr.path
+                .segments
+                .first()
+                .map(|segment| segment.ident == "generic_new")
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => None,
+        [attribute] => Some(*attribute),
+        [attribute, ..] => {
+            emit_error!(item.span(), "Can't specify `generic_new` more than once");
+            Some(*attribute)
+        }
+    }
+}
+
+/// Report that `#[generic_new(...)]` couldn't be parsed, without aborting
+pub fn emit_parse_error(error: syn::Error) {
+    // The compile error spans the same tokens as `error`, where `error.span()` is only the first
+    emit_error!(
+        error.to_compile_error(),
+        "Couldn't parse attributes: {}",
+        error
+    )
+}
+
+/// Type of expression users can add to a field
+#[allow(dead_code)]
 #[derive(Debug, Parse)]
 pub enum UserAttribute {
     #[peek_with(ident_is("ignore"), name = "ignore")]
@@ -17,11 +152,120 @@ pub enum UserAttribute {
     InputType(Ident, Token![=], Type),
     #[peek_with(ident_is("converter"), name = "converter")]
     Converter(Ident, Token![=], Expr),
+    #[peek_with(ident_is("try_converter"), name = "try_converter")]
+    TryConverter(Ident, Token![=], Expr),
+    #[peek_with(ident_is("default"), name = "default")]
+    Default(Ident, #[peek(Token![=])] Option<DefaultValue>),
+    #[peek_with(ident_is("into"), name = "into")]
+    Into(Ident),
+    #[peek_with(ident_is("parse"), name = "parse")]
+    Parse(Ident),
+}
+
+/// Type of expression users can add to a struct or enum
+#[allow(dead_code)]
+#[derive(Debug, Parse)]
+pub enum ContainerAttribute {
+    #[peek_with(ident_is("error"), name = "error")]
+    Error(Ident, Token![=], Box<Type>),
+    #[peek_with(ident_is("builder"), name = "builder")]
+    Builder(Ident),
+    #[peek_with(ident_is("vis"), name = "vis")]
+    Vis(Ident, Token![=], Visibility),
+    #[peek_with(ident_is("name"), name = "name")]
+    Name(Ident, Token![=], Ident),
+    #[peek(Token![const], name = "const")]
+    Const(Token![const]),
+    #[peek_with(ident_is("constructor"), name = "constructor")]
+    Constructor(Ident, ConstructorAttributes),
+    #[peek_with(ident_is("alias"), name = "alias")]
+    Alias(Ident, Parenthesized<Alias>),
+    #[peek_with(ident_is("into"), name = "into")]
+    Into(Ident),
+    #[peek_with(ident_is("doc"), name = "doc")]
+    Doc(Ident, Token![=], LitStr),
+}
+
+impl UserAttribute {
+    /// Parse, with a helpful error if the option isn't a field option
+    pub fn parse_checked(input: ParseStream) -> syn::Result<Self> {
+        check_option(input, FIELD_OPTIONS)?;
+        input.parse()
+    }
+}
+
+impl ContainerAttribute {
+    /// Parse, with a helpful error if the option isn't a struct or enum option
+    pub fn parse_checked(input: ParseStream) -> syn::Result<Self> {
+        check_option(input, CONTAINER_OPTIONS)?;
+        input.parse()
+    }
+}
+
+impl ConstructorAttribute {
+    /// Parse, with a helpful error if the option can't be given inside `constructor(...)`
+    pub fn parse_checked(input: ParseStream) -> syn::Result<Self> {
+        check_option(input, CONSTRUCTOR_OPTIONS)?;
+        input.parse()
+    }
+}
+
+/// `MyString = String`, telling us that `MyString` is a type alias
+#[allow(dead_code)]
+#[derive(Debug, Clone, Parse)]
+pub struct Alias {
+    pub name: Ident,
+    eq: Token![=],
+    pub ty: Type,
+}
+
+/// Type of expression users can add to a `constructor(...)`
+#[allow(dead_code)]
+#[derive(Debug, Parse)]
+pub enum ConstructorAttribute {
+    #[peek_with(ident_is("name"), name = "name")]
+    Name(Ident, Token![=], Ident),
+    #[peek_with(ident_is("vis"), name = "vis")]
+    Vis(Ident, Token![=], Visibility),
+    #[peek(Token![const], name = "const")]
+    Const(Token![const]),
+    #[peek_with(ident_is("defaults"), name = "defaults")]
+    Defaults(Ident, Parenthesized<Member>),
+}
+
+/// `(name = ..., ...)` after `constructor`
+#[allow(dead_code)]
+#[derive(Debug, Parse)]
+pub struct ConstructorAttributes {
+    #[paren]
+    paren: Paren,
+    #[inside(paren)]
+    #[parse_terminated(ConstructorAttribute::parse_checked)]
+    pub items: Punctuated<ConstructorAttribute, Comma>,
+}
+
+/// `(a, b, ...)`
+#[allow(dead_code)]
+#[derive(Debug, Parse)]
+pub struct Parenthesized<T: syn::parse::Parse> {
+    #[paren]
+    paren: Paren,
+    #[inside(paren)]
+    #[parse_terminated(T::parse)]
+    pub items: Punctuated<T, Comma>,
+}
+
+/// `= expr` after `default`
+#[allow(dead_code)]
+#[derive(Debug, Parse)]
+pub struct DefaultValue {
+    eq: Token![=],
+    pub expr: Expr,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::UserAttribute;
+    use super::{edit_distance, ConstructorAttribute, ContainerAttribute, UserAttribute};
     use quote::quote;
 
     #[test]
@@ -32,6 +276,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_into() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<UserAttribute>(quote!(into))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, UserAttribute::Into(_)));
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(into))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Into(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_parse() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<UserAttribute>(quote!(parse))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, UserAttribute::Parse(_)));
+        Ok(())
+    }
+
     #[test]
     fn parse_input_type() -> anyhow::Result<()> {
         let parsed = syn::parse2::<UserAttribute>(quote!(ty = impl IntoIterator<Item = usize>))?;
@@ -48,10 +311,158 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_try_converter() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<UserAttribute>(quote!(try_converter = str::parse))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, UserAttribute::TryConverter(_, _, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_default() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<UserAttribute>(quote!(default))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, UserAttribute::Default(_, None)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_default_expr() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<UserAttribute>(quote!(default = vec![1, 2, 3]))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, UserAttribute::Default(_, Some(_))));
+        Ok(())
+    }
+
     #[test]
     fn dont_parse_unknown() {
         let res = syn::parse2::<UserAttribute>(quote!(foo));
         println!("{res:?}");
         assert!(res.is_err());
     }
+
+    #[test]
+    fn parse_error() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(error = std::io::Error))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Error(_, _, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_builder() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(builder))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Builder(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_vis() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(vis = pub(crate)))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Vis(_, _, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_name() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(name = create))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Name(_, _, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_const() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(const))?;
+        println!("{parsed:?}");
+        assert!(matches!(parsed, ContainerAttribute::Const(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_constructor() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(constructor(
+            name = with_defaults,
+            defaults(cache, retries)
+        )))?;
+        println!("{parsed:?}");
+        match parsed {
+            ContainerAttribute::Constructor(_, attributes) => assert_eq!(attributes.items.len(), 2),
+            _ => panic!("expected a constructor"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_defaults() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ConstructorAttribute>(quote!(defaults(cache, 0)))?;
+        println!("{parsed:?}");
+        match parsed {
+            ConstructorAttribute::Defaults(_, members) => assert_eq!(members.items.len(), 2),
+            _ => panic!("expected defaults"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_alias() -> anyhow::Result<()> {
+        let parsed = syn::parse2::<ContainerAttribute>(quote!(alias(
+            MyString = String,
+            Names = Vec<MyString>
+        )))?;
+        println!("{parsed:?}");
+        match parsed {
+            ContainerAttribute::Alias(_, aliases) => assert_eq!(aliases.items.len(), 2),
+            _ => panic!("expected aliases"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("converter", "converter"), 0);
+        assert_eq!(edit_distance("conveter", "converter"), 1);
+        assert_eq!(edit_distance("buidler", "builder"), 2);
+        assert_eq!(edit_distance("", "ty"), 2);
+        assert_eq!(edit_distance("ignore", "into"), 4);
+    }
+
+    #[test]
+    fn parse_unknown_option() {
+        let error = syn::parse::Parser::parse2(UserAttribute::parse_checked, quote!(conveter = f))
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("unknown option `conveter`, did you mean `converter`?"));
+        let error = syn::parse::Parser::parse2(ContainerAttribute::parse_checked, quote!(whatever))
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("unknown option `whatever`. Expected"));
+    }
+
+    #[test]
+    fn parse_wrong_level() {
+        let error =
+            syn::parse::Parser::parse2(UserAttribute::parse_checked, quote!(builder)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`builder` can't be used on fields, only on structs and enums"
+        );
+        let error = syn::parse::Parser::parse2(ContainerAttribute::parse_checked, quote!(default))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`default` can't be used on structs and enums, only on fields"
+        );
+        let error = syn::parse2::<ContainerAttribute>(quote!(constructor(name = new, builder)))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`builder` can't be used inside `constructor(...)`, only on structs and enums"
+        );
+    }
 }
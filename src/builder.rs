@@ -0,0 +1,83 @@
+use crate::{config::ContainerConfig, construct, error_type, field::FieldConfig, vis};
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Fields, Ident};
+
+/// A `#NameBuilder` struct, with a setter for each field, and a `build` method.
+/// Setters accept the same types as the arguments to `new`.
+/// `build` fails if any fields without a default haven't been set,
+/// with the container's error made `From` a message.
+/// Setters for parsed fields fail with `parse_error`, unless the container has an error
+pub fn make_builder(
+    derive_input: &DeriveInput,
+    fields: &Fields,
+    field_infos: &[FieldConfig],
+    container_config: &ContainerConfig,
+    parse_error: &Ident,
+) -> TokenStream2 {
+    if let Fields::Unnamed(_) = fields {
+        emit_error!(derive_input, "`builder` is not supported on tuple structs");
+        return quote!();
+    }
+    let user_ident = &derive_input.ident;
+    let builder_ident = format_ident!("{}Builder", user_ident);
+    let builder_vis = match &container_config.vis {
+        Some(vis) => vis,
+        None => &derive_input.vis,
+    };
+    let vis = vis(&container_config.vis);
+    let generics = &derive_input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let builder_fields = field_infos.iter().map(FieldConfig::builder_field);
+    let builder_inits = field_infos.iter().map(FieldConfig::builder_init);
+    let error = error_type(container_config, None);
+    let setters = field_infos
+        .iter()
+        .filter(|field_info| {
+            let clashes = field_info.input_name() == "build";
+            if clashes {
+                emit_error!(
+                    field_info.span(),
+                    "A field called `build` would clash with `{}::build`", builder_ident;
+                    help = "rename the field, or leave out `builder`"
+                );
+            }
+            !clashes
+        })
+        .map(|field_info| {
+            let error = error_type(container_config, field_info.parsed().then_some(parse_error));
+            field_info.setter(&vis, &error)
+        });
+    let builds = field_infos.iter().map(FieldConfig::build);
+    let constructor = construct(quote!(#user_ident), fields, field_infos);
+
+    let builder_doc = format!("Builder for [`{user_ident}`]");
+    let builder_fn_doc = format!("Create a [`{builder_ident}`]");
+    quote! {
+        #[doc = #builder_doc]
+        #builder_vis struct #builder_ident #generics #where_clause {
+            #(#builder_fields,)*
+        }
+
+        impl #impl_generics #user_ident #ty_generics #where_clause {
+            #[doc = #builder_fn_doc]
+            #vis fn builder() -> #builder_ident #ty_generics {
+                #builder_ident {
+                    #(#builder_inits,)*
+                }
+            }
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            #(#setters)*
+
+            /// Fails if any fields without a default haven't been set
+            #vis fn build(self) -> ::std::result::Result<#user_ident #ty_generics, #error> {
+                #(#builds;)*
+                ::std::result::Result::Ok(#constructor)
+            }
+        }
+    }
+}
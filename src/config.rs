@@ -1,51 +1,357 @@
-use proc_macro_error::abort;
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_error::emit_error;
+use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    Expr, Token, Type,
+    spanned::Spanned,
+    Expr, ExprClosure, Ident, LitStr, Member, Pat, PatType, Token, Type, Visibility,
 };
 
-use crate::attributes::UserAttribute;
+use crate::attributes::{Alias, ConstructorAttribute, ContainerAttribute, UserAttribute};
 
 /// Config added by the user
 #[derive(Debug)]
-pub enum UserConfig {
+pub struct UserConfig {
+    pub conversion: Conversion,
+    /// If set, this field isn't an argument to `new`, and is initialised with this instead
+    pub default: Option<FieldDefault>,
+}
+
+/// What a field which isn't an argument is initialised with
+#[derive(Debug, Clone)]
+pub enum FieldDefault {
+    /// `Default::default()`, which can't be called in a `const fn`
+    Trait,
+    /// `default = ...`
+    Value(Box<Expr>),
+}
+
+impl ToTokens for FieldDefault {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            FieldDefault::Trait => tokens.extend(quote!(::std::default::Default::default())),
+            FieldDefault::Value(expr) => expr.to_tokens(tokens),
+        }
+    }
+}
+
+/// How the argument to `new` becomes the field
+#[derive(Debug)]
+pub enum Conversion {
+    /// Use a magic conversion if we have one
+    Magic,
     Ignore,
-    Custom(Type, Expr),
+    /// Accept `impl Into<T>`
+    Into,
+    /// Accept `impl AsRef<str>`, and parse it with `FromStr`
+    Parse,
+    Custom(Box<Type>, Box<Expr>),
+    /// The converter returns a `Result`
+    TryCustom(Box<Type>, Box<Expr>),
+    /// Only `ty` was given, so the converter is inferred from it
+    InferConverter(Box<Type>),
 }
 
 impl Parse for UserConfig {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let p = input.parse_terminated::<_, Token![,]>(UserAttribute::parse)?;
-        let mut ignore = false;
+        let p = input.parse_terminated::<_, Token![,]>(UserAttribute::parse_checked)?;
+        let mut ignore = None;
+        let mut into = None;
+        let mut parse = None;
         let mut input_type = None;
         let mut converter = None;
+        let mut try_converter = None;
+        let mut default = None;
         for user_attribute in p {
             match user_attribute {
-                UserAttribute::Ignore(_) => match ignore {
-                    true => abort!(input.span(), "Cannot specify `ignore` more than once"),
-                    false => ignore = true,
-                },
-                UserAttribute::InputType(_, _, t) => {
-                    if let Some(_) = input_type.replace(t) {
-                        abort!(input.span(), "Can't specify `input_type` more than once")
+                UserAttribute::Ignore(i) => set_once(&mut ignore, i, ()),
+                UserAttribute::Into(i) => set_once(&mut into, i, ()),
+                UserAttribute::Parse(i) => set_once(&mut parse, i, ()),
+                UserAttribute::InputType(i, _, t) => set_once(&mut input_type, i, t),
+                UserAttribute::Converter(i, _, e) => set_once(&mut converter, i, (e, false)),
+                UserAttribute::TryConverter(i, _, e) => set_once(&mut try_converter, i, (e, true)),
+                UserAttribute::Default(i, value) => {
+                    let value = match value {
+                        Some(value) => FieldDefault::Value(Box::new(value.expr)),
+                        None => FieldDefault::Trait,
+                    };
+                    set_once(&mut default, i, value)
+                }
+            }
+        }
+        let converter = match (converter, try_converter) {
+            (Some(converter), Some((t, _))) => {
+                emit_error!(
+                    t, "`converter` is mutually exclusive with `try_converter`";
+                    note = converter.0.span() => "`converter` specified here"
+                );
+                Some(converter)
+            }
+            (converter, try_converter) => converter.or(try_converter),
+        };
+        // Each of these decides what the argument is, so only one may be given
+        let choices = [
+            ignore.as_ref().map(|(i, _)| i),
+            into.as_ref().map(|(i, _)| i),
+            parse.as_ref().map(|(i, _)| i),
+            input_type
+                .as_ref()
+                .map(|(i, _)| i)
+                .or(converter.as_ref().map(|(i, _)| i)),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+        if let [first, others @ ..] = choices.as_slice() {
+            for other in others {
+                emit_error!(
+                    other, "`{}` is mutually exclusive with `{}`", other, first;
+                    note = first.span() => "`{}` specified here", first
+                )
+            }
+        }
+        let conversion = match (ignore, into, parse, input_type, converter) {
+            (Some(_), ..) => Conversion::Ignore,
+            (None, Some(_), ..) => Conversion::Into,
+            (None, None, Some(_), ..) => Conversion::Parse,
+            (None, None, None, Some((_, t)), Some((_, (e, false)))) => {
+                Conversion::Custom(Box::new(t), Box::new(e))
+            }
+            (None, None, None, Some((_, t)), Some((_, (e, true)))) => {
+                Conversion::TryCustom(Box::new(t), Box::new(e))
+            }
+            (None, None, None, Some((_, t)), None) => Conversion::InferConverter(Box::new(t)),
+            (None, None, None, None, Some((i, (e, fallible)))) => match closure_input_type(&e) {
+                Some(t) if fallible => Conversion::TryCustom(Box::new(t), Box::new(e)),
+                Some(t) => Conversion::Custom(Box::new(t), Box::new(e)),
+                None => {
+                    match e {
+                        Expr::Closure(_) => emit_error!(
+                            i, "Can't infer `ty` from `{}`", i;
+                            help = "give the closure's parameter a type, like `|s: &str| ...`, or add `ty = ...`"
+                        ),
+                        _ => emit_error!(
+                            i, "Can't infer `ty` from `{}`", i;
+                            help = "only a closure's parameter type can be read, so add `ty = ...`"
+                        ),
                     }
+                    Conversion::Ignore
+                }
+            },
+            (None, None, None, None, None) => {
+                if default.is_none() {
+                    emit_error!(input.span(), "No attributes found")
+                }
+                Conversion::Magic
+            }
+        };
+        Ok(UserConfig {
+            conversion,
+            default: default.map(|(_, value)| value),
+        })
+    }
+}
+
+/// `T` for a converter like `|t: T| ...`
+fn closure_input_type(converter: &Expr) -> Option<Type> {
+    match converter {
+        Expr::Closure(ExprClosure { inputs, .. }) => match inputs.iter().collect::<Vec<_>>()[..] {
+            [Pat::Type(PatType { ty, .. })] => Some(*ty.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Store `value` in `slot`, or report that `ident` was given more than once
+fn set_once<T>(slot: &mut Option<(Ident, T)>, ident: Ident, value: T) {
+    match slot {
+        Some((first, _)) => emit_error!(
+            ident, "Can't specify `{}` more than once", ident;
+            note = first.span() => "first specified here"
+        ),
+        None => *slot = Some((ident, value)),
+    }
+}
+
+/// Config added by the user to the struct or enum
+#[derive(Debug, Default)]
+pub struct ContainerConfig {
+    /// Error type for fallible constructors
+    pub error: Option<Type>,
+    /// Generate a builder as well as the constructor
+    pub builder: bool,
+    /// Visibility of the constructor, rather than `pub`
+    pub vis: Option<Visibility>,
+    /// Name of the constructor, rather than `new`
+    pub name: Option<Ident>,
+    /// Make the constructor a `const fn`
+    pub constness: bool,
+    /// Constructors to generate instead of `new`
+    pub constructors: Vec<ConstructorConfig>,
+    /// Type aliases which should get the same magic as the types they stand for
+    pub aliases: Vec<Alias>,
+    /// Fields without magic accept `impl Into<T>`
+    pub into: bool,
+    /// Doc comment for the constructors, rather than one describing the arguments
+    pub doc: Option<LitStr>,
+}
+
+impl Parse for ContainerConfig {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let p = input.parse_terminated::<_, Token![,]>(ContainerAttribute::parse_checked)?;
+        if p.is_empty() {
+            emit_error!(input.span(), "No attributes found")
+        }
+        let mut error = None;
+        let mut builder = None;
+        let mut into = None;
+        let mut vis = None;
+        let mut name = None;
+        let mut constness = None;
+        let mut doc = None;
+        let mut constructors = Vec::<ConstructorConfig>::new();
+        let mut aliases = Vec::<Alias>::new();
+        for container_attribute in p {
+            match container_attribute {
+                ContainerAttribute::Error(i, _, t) => set_once(&mut error, i, *t),
+                ContainerAttribute::Builder(i) => set_once(&mut builder, i, ()),
+                ContainerAttribute::Doc(i, _, d) => set_once(&mut doc, i, d),
+                ContainerAttribute::Into(i) => set_once(&mut into, i, ()),
+                ContainerAttribute::Vis(i, _, v) => set_once(&mut vis, i, v),
+                ContainerAttribute::Name(i, _, n) => set_once(&mut name, i, n),
+                ContainerAttribute::Const(c) => {
+                    set_once(&mut constness, Ident::new("const", c.span), ())
+                }
+                ContainerAttribute::Constructor(ident, attributes) => {
+                    let Some(constructor) = ConstructorConfig::new(ident, attributes.items) else {
+                        continue;
+                    };
+                    match constructors
+                        .iter()
+                        .find(|existing| existing.name == constructor.name)
+                    {
+                        Some(existing) => emit_error!(
+                            constructor.name, "Can't have more than one constructor called `{}`", constructor.name;
+                            note = existing.name.span() => "first specified here"
+                        ),
+                        None => constructors.push(constructor),
+                    }
+                }
+                ContainerAttribute::Alias(_, new_aliases) => {
+                    for alias in new_aliases.items {
+                        match aliases.iter().find(|existing| existing.name == alias.name) {
+                            Some(existing) => emit_error!(
+                                alias.name, "Can't alias `{}` more than once", alias.name;
+                                note = existing.name.span() => "first specified here"
+                            ),
+                            None => aliases.push(alias),
+                        }
+                    }
+                }
+            }
+        }
+        if let (Some((i, _)), [first, ..]) = (&name, constructors.as_slice()) {
+            emit_error!(
+                i, "`name` is mutually exclusive with `constructor`";
+                note = first.name.span() => "`constructor` specified here"
+            )
+        }
+        let vis = vis.map(|(_, vis)| vis);
+        let constness = constness.is_some();
+        // Constructors inherit top level options
+        for constructor in constructors.iter_mut() {
+            if constructor.vis.is_none() {
+                constructor.vis = vis.clone()
+            }
+            constructor.constness |= constness;
+        }
+        Ok(ContainerConfig {
+            error: error.map(|(_, error)| error),
+            builder: builder.is_some(),
+            vis,
+            name: name.map(|(_, name)| name),
+            constness,
+            constructors,
+            aliases,
+            into: into.is_some(),
+            doc: doc.map(|(_, doc)| doc),
+        })
+    }
+}
+
+impl ContainerConfig {
+    /// A constructor called `name`, with the top level options
+    pub fn constructor(&self, name: Ident) -> ConstructorConfig {
+        ConstructorConfig {
+            name,
+            vis: self.vis.clone(),
+            constness: self.constness,
+            defaults: None,
+        }
+    }
+}
+
+/// A constructor the user has asked for with `constructor(...)`
+#[derive(Debug)]
+pub struct ConstructorConfig {
+    pub name: Ident,
+    /// Visibility of the constructor, rather than the top level `vis`
+    pub vis: Option<Visibility>,
+    /// Make the constructor a `const fn`
+    pub constness: bool,
+    /// If set, exactly these fields aren't arguments, and are initialised with their default instead.
+    /// Otherwise, fields with a `default` of their own aren't arguments
+    pub defaults: Option<Vec<Member>>,
+}
+
+impl ConstructorConfig {
+    /// None if it has no name, which is reported as an error
+    fn new(
+        constructor: Ident,
+        attributes: impl IntoIterator<Item = ConstructorAttribute>,
+    ) -> Option<Self> {
+        let mut name = None;
+        let mut vis = None;
+        let mut constness = None;
+        let mut defaults = None;
+        for constructor_attribute in attributes {
+            match constructor_attribute {
+                ConstructorAttribute::Name(i, _, n) => set_once(&mut name, i, n),
+                ConstructorAttribute::Vis(i, _, v) => set_once(&mut vis, i, v),
+                ConstructorAttribute::Const(c) => {
+                    set_once(&mut constness, Ident::new("const", c.span), ())
                 }
-                UserAttribute::Converter(_, _, e) => {
-                    if let Some(_) = converter.replace(e) {
-                        abort!(input.span(), "Can't specify `converter` more than once")
+                ConstructorAttribute::Defaults(i, members) => {
+                    let mut unique = Vec::<Member>::new();
+                    for member in members.items {
+                        match unique.iter().find(|first| **first == member) {
+                            Some(first) => emit_error!(
+                                member, "Can't specify `{}` more than once", quote!(#member);
+                                note = first.span() => "first specified here"
+                            ),
+                            None => unique.push(member),
+                        }
                     }
+                    set_once(&mut defaults, i, unique)
                 }
             }
         }
-        match (ignore, input_type, converter) {
-            (false, None, None) => abort!(input.span(), "No attributes found"),
-            (true, None, None) => Ok(UserConfig::Ignore),
-            (true, _, _) => abort!(
-                input.span(),
-                "`ignore` is mutually exclusive with other options"
-            ),
-            (false, Some(t), Some(e)) => Ok(UserConfig::Custom(t, e)),
-            (false, _, _) => abort!(input.span(), "Must provide both `ty` and `converter`"),
+        let vis = vis.map(|(_, vis)| vis);
+        let constness = constness.is_some();
+        let defaults = defaults.map(|(_, defaults)| defaults);
+        match name {
+            Some((_, name)) => Some(ConstructorConfig {
+                name,
+                vis,
+                constness,
+                defaults,
+            }),
+            None => {
+                emit_error!(constructor, "Must provide a `name` for each `constructor`");
+                None
+            }
         }
     }
 }
@@ -54,32 +360,137 @@ impl Parse for UserConfig {
 mod tests {
     use quote::quote;
 
-    use super::UserConfig;
+    use super::{ContainerConfig, Conversion, UserConfig};
     #[test]
     fn parse_ignore() -> anyhow::Result<()> {
         let config = syn::parse2::<UserConfig>(quote!(ignore))?;
         println!("{config:?}");
-        assert!(matches!(config, UserConfig::Ignore));
+        assert!(matches!(config.conversion, Conversion::Ignore));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_into() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(into, default = 1))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::Into));
+        assert!(config.default.is_some());
+        let config = syn::parse2::<ContainerConfig>(quote!(into))?;
+        println!("{config:?}");
+        assert!(config.into);
         Ok(())
     }
 
-    #[should_panic]
     #[test]
-    fn parse_nothing() {
-        let _ = syn::parse2::<UserConfig>(quote!());
+    fn parse_parse() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(parse))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::Parse));
+        Ok(())
     }
 
     #[test]
     fn parse_custom() -> anyhow::Result<()> {
         let config = syn::parse2::<UserConfig>(quote!(ty = usize, converter = |u| format!("{u}")))?;
         println!("{config:?}");
-        assert!(matches!(config, UserConfig::Custom(_, _)));
+        assert!(matches!(config.conversion, Conversion::Custom(_, _)));
         Ok(())
     }
 
-    #[should_panic]
     #[test]
-    fn parse_incomplete() {
-        let _ = syn::parse2::<UserConfig>(quote!(ty = usize));
+    fn parse_inferred() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(ty = impl Into<usize>))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::InferConverter(_)));
+        let config = syn::parse2::<UserConfig>(quote!(converter = |s: &str| s.len()))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::Custom(_, _)));
+        let config = syn::parse2::<UserConfig>(quote!(try_converter = |s: &str| s.parse()))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::TryCustom(_, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_default() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(default))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::Magic));
+        assert!(config.default.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_ignore_default() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(ignore, default = 1 + 1))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::Ignore));
+        assert!(config.default.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_try_custom() -> anyhow::Result<()> {
+        let config = syn::parse2::<UserConfig>(quote!(ty = &str, try_converter = str::parse))?;
+        println!("{config:?}");
+        assert!(matches!(config.conversion, Conversion::TryCustom(_, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_error() -> anyhow::Result<()> {
+        let config = syn::parse2::<ContainerConfig>(quote!(error = std::io::Error))?;
+        println!("{config:?}");
+        assert!(config.error.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_builder_and_error() -> anyhow::Result<()> {
+        let config = syn::parse2::<ContainerConfig>(quote!(builder, error = std::io::Error))?;
+        println!("{config:?}");
+        assert!(config.builder);
+        assert!(config.error.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_signature() -> anyhow::Result<()> {
+        let config =
+            syn::parse2::<ContainerConfig>(quote!(vis = pub(crate), name = create, const))?;
+        println!("{config:?}");
+        assert!(matches!(config.vis, Some(syn::Visibility::Restricted(_))));
+        assert_eq!(config.name.unwrap(), "create");
+        assert!(config.constness);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_constructors() -> anyhow::Result<()> {
+        let config = syn::parse2::<ContainerConfig>(quote!(
+            constructor(name = with_defaults, defaults(cache, retries)),
+            constructor(name = new, vis = pub(crate), const)
+        ))?;
+        println!("{config:?}");
+        let [with_defaults, new] = config.constructors.as_slice() else {
+            panic!("expected two constructors")
+        };
+        assert_eq!(with_defaults.name, "with_defaults");
+        assert_eq!(with_defaults.defaults.as_ref().map(Vec::len), Some(2));
+        assert_eq!(new.name, "new");
+        assert!(new.vis.is_some());
+        assert!(new.constness);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_container_aliases() -> anyhow::Result<()> {
+        let config = syn::parse2::<ContainerConfig>(quote!(
+            alias(MyString = String),
+            alias(Names = Vec<MyString>)
+        ))?;
+        println!("{config:?}");
+        assert_eq!(config.aliases.len(), 2);
+        Ok(())
     }
 }
@@ -0,0 +1,285 @@
+use crate::{config::ContainerConfig, field::FieldConfig};
+use proc_macro2::{Delimiter, Spacing, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+
+/// `#[doc = "..."]` for a constructor of `subject`, listing its arguments and defaults,
+/// or the container's `doc` if the user gave one
+pub fn constructor_docs(
+    subject: &str,
+    field_infos: &[FieldConfig],
+    container_config: &ContainerConfig,
+) -> TokenStream2 {
+    if let Some(doc) = &container_config.doc {
+        return quote!(#[doc = #doc]);
+    }
+    let mut lines = vec![format!("Create a new {subject}.")];
+    let arguments = field_infos
+        .iter()
+        .filter_map(FieldConfig::argument_doc)
+        .collect::<Vec<_>>();
+    if !arguments.is_empty() {
+        lines.extend([String::new(), String::from("# Arguments"), String::new()]);
+        lines.extend(arguments);
+    }
+    let defaults = field_infos
+        .iter()
+        .filter_map(FieldConfig::default_doc)
+        .collect::<Vec<_>>();
+    if !defaults.is_empty() {
+        lines.extend([String::new(), String::from("# Defaults"), String::new()]);
+        lines.extend(defaults);
+    }
+    if field_infos.iter().any(FieldConfig::fallible) {
+        lines.extend([
+            String::new(),
+            String::from("# Errors"),
+            String::new(),
+            String::from("If any of the arguments can't be converted."),
+        ]);
+    }
+    let doc = lines.join("\n");
+    quote!(#[doc = #doc])
+}
+
+/// A type as a user would write it, rather than as spaced-out tokens.
+/// Only the spacing changes, so paths and literals are kept as they are
+pub fn type_doc(ty: impl ToTokens) -> String {
+    let mut pieces = Vec::<Piece>::new();
+    let mut tokens = ty.into_token_stream().into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let piece = match token {
+            TokenTree::Ident(ident) => Piece::Word(ident.to_string()),
+            TokenTree::Literal(literal) => Piece::Word(literal.to_string()),
+            // A lifetime, like `'a`
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => match tokens.next() {
+                Some(TokenTree::Ident(ident)) => Piece::Word(format!("'{ident}")),
+                _ => Piece::Op(String::from("'")),
+            },
+            TokenTree::Punct(punct) => {
+                let mut op = String::from(punct.as_char());
+                let mut spacing = punct.spacing();
+                while spacing == Spacing::Joint {
+                    match tokens.peek() {
+                        Some(TokenTree::Punct(next)) if next.as_char() != '\'' => {
+                            op.push(next.as_char());
+                            spacing = next.spacing();
+                            tokens.next();
+                        }
+                        _ => break,
+                    }
+                }
+                Piece::Op(op)
+            }
+            TokenTree::Group(group) => {
+                let inner = type_doc(group.stream());
+                let printed = match group.delimiter() {
+                    Delimiter::Parenthesis => format!("({inner})"),
+                    Delimiter::Bracket => format!("[{inner}]"),
+                    Delimiter::Brace => format!("{{ {inner} }}"),
+                    Delimiter::None => inner,
+                };
+                Piece::Group(group.delimiter(), printed)
+            }
+        };
+        pieces.push(piece);
+    }
+    let mut doc = String::new();
+    for (n, piece) in pieces.iter().enumerate() {
+        if n > 0 && spaced(&pieces[n - 1], piece) {
+            doc.push(' ');
+        }
+        match piece {
+            Piece::Word(s) | Piece::Op(s) | Piece::Group(_, s) => doc.push_str(s),
+        }
+    }
+    doc
+}
+
+/// Part of a type in [`type_doc`]
+enum Piece {
+    /// An identifier, literal or lifetime
+    Word(String),
+    /// Punctuation, joined up like `::` or `->`
+    Op(String),
+    /// Something in brackets, already printed
+    Group(Delimiter, String),
+}
+
+/// Whether there's a space between `previous` and `next` in [`type_doc`]
+fn spaced(previous: &Piece, next: &Piece) -> bool {
+    let is_binary = |op: &str| matches!(op, "=" | "->" | "+" | "=>");
+    match (previous, next) {
+        (Piece::Word(_), Piece::Word(_)) => true,
+        (Piece::Op(op), _) if matches!(op.as_str(), "," | ";") || is_binary(op) => true,
+        (_, Piece::Op(op)) if is_binary(op) => true,
+        // Like `for<'a> fn(&'a u8)`
+        (Piece::Op(op), Piece::Word(_)) => op == ">",
+        // Only function types take their arguments in parentheses, unlike `&mut (u8, u8)`
+        (Piece::Word(word), Piece::Group(Delimiter::Parenthesis, _)) => {
+            !matches!(word.as_str(), "fn" | "Fn" | "FnMut" | "FnOnce")
+        }
+        (Piece::Word(_), Piece::Group(..)) => true,
+        (Piece::Group(..), Piece::Word(_) | Piece::Group(..)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{constructor_docs, type_doc};
+    use crate::{config::ContainerConfig, field::make_field_configs};
+    use quote::{format_ident, quote};
+    use syn::{Data, DeriveInput};
+
+    fn docs(derive_input: DeriveInput) -> String {
+        let container_config = match derive_input.attrs.first() {
+            Some(attribute) => attribute.parse_args::<ContainerConfig>().unwrap(),
+            None => ContainerConfig::default(),
+        };
+        let fields = match derive_input.data {
+            Data::Struct(user_struct) => user_struct.fields,
+            _ => unreachable!(),
+        };
+        let field_infos = make_field_configs(
+            &fields,
+            &container_config,
+            &format_ident!("{}ParseError", derive_input.ident),
+        );
+        let docs = constructor_docs("`Foo`", &field_infos, &container_config);
+        let attribute = syn::parse::Parser::parse2(syn::Attribute::parse_outer, docs).unwrap();
+        match attribute[0].parse_meta().unwrap() {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(doc),
+                ..
+            }) => doc.value(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn constructor_docs_list_arguments() {
+        let docs = docs(
+            syn::parse2(quote! {
+                struct Foo {
+                    /// What it's called
+                    name: String,
+                    count: usize,
+                    #[generic_new(parse)]
+                    port: u16,
+                    #[generic_new(default)]
+                    cache: Vec<usize>,
+                }
+            })
+            .unwrap(),
+        );
+        assert_eq!(
+            docs,
+            "Create a new `Foo`.
+
+# Arguments
+
+* `name`: `impl AsRef<str>`, converted into `String`
+  What it's called
+* `count`: `usize`
+* `port`: `impl AsRef<str>`, converted into `u16`, which may fail
+
+# Defaults
+
+* `cache`: `Default::default()`
+
+# Errors
+
+If any of the arguments can't be converted."
+        );
+    }
+
+    #[test]
+    fn constructor_docs_override() {
+        let docs = docs(
+            syn::parse2(quote! {
+                #[generic_new(doc = "Make a `Foo`")]
+                struct Foo {
+                    name: String,
+                }
+            })
+            .unwrap(),
+        );
+        assert_eq!(docs, "Make a `Foo`");
+    }
+
+    fn type_doc_of(ty: &str) -> String {
+        type_doc(syn::parse_str::<syn::Type>(ty).unwrap())
+    }
+
+    #[test]
+    fn type_docs() {
+        assert_eq!(type_doc_of("Box<[u8]>"), "Box<[u8]>");
+        assert_eq!(type_doc_of("&'a str"), "&'a str");
+        assert_eq!(type_doc_of("&mut (u8, u8)"), "&mut (u8, u8)");
+        assert_eq!(type_doc_of("std::string::String"), "std::string::String");
+        assert_eq!(
+            type_doc_of("::std::collections::HashMap<String, [u8; 4]>"),
+            "::std::collections::HashMap<String, [u8; 4]>"
+        );
+        assert_eq!(
+            type_doc_of("impl Into<u64> + Send"),
+            "impl Into<u64> + Send"
+        );
+        assert_eq!(
+            type_doc_of("Box<dyn for<'a> Fn(&'a str) -> usize>"),
+            "Box<dyn for<'a> Fn(&'a str) -> usize>"
+        );
+        assert_eq!(
+            type_doc_of("Foo<{ \":: std :: x\" }>"),
+            "Foo<{ \":: std :: x\" }>"
+        );
+    }
+
+    #[test]
+    fn constructor_docs_leave_expressions() {
+        let docs = docs(
+            syn::parse2(quote! {
+                struct Foo {
+                    x: usize,
+                    #[generic_new(default = x > 1)]
+                    big: bool,
+                    #[generic_new(default = String::from(":: std :: x"))]
+                    name: String,
+                }
+            })
+            .unwrap(),
+        );
+        assert!(docs.contains("* `big`: `x > 1`"), "{docs}");
+        assert!(
+            docs.contains(r#"* `name`: `String :: from (":: std :: x")`"#),
+            "{docs}"
+        );
+    }
+
+    #[test]
+    fn constructor_docs_magic() {
+        let docs = docs(
+            syn::parse2(quote! {
+                struct Foo {
+                    paths: HashMap<String, Vec<PathBuf>>,
+                    pair: (Option<OsString>, usize),
+                    shared: Arc<Mutex<[String; 2]>>,
+                    #[generic_new(into)]
+                    id: ::std::primitive::u64,
+                }
+            })
+            .unwrap(),
+        );
+        assert_eq!(
+            docs,
+            "Create a new `Foo`.
+
+# Arguments
+
+* `paths`: `impl IntoIterator<Item = (impl AsRef<str>, impl IntoIterator<Item = impl AsRef<Path>>)>`, converted into `HashMap<String, Vec<PathBuf>>`
+* `pair`: `(Option<impl AsRef<OsStr>>, usize)`, converted into `(Option<OsString>, usize)`
+* `shared`: `[impl AsRef<str>; 2]`, converted into `Arc<Mutex<[String; 2]>>`
+* `id`: `impl Into<::std::primitive::u64>`, converted into `::std::primitive::u64`"
+        );
+    }
+}
@@ -1,12 +1,16 @@
-use crate::config::UserConfig;
+use crate::{
+    attributes::{emit_parse_error, generic_new_attribute},
+    config::{ContainerConfig, Conversion, FieldDefault, UserConfig},
+    docs::type_doc,
+    magic::{classify, into, Magic},
+};
 use log::debug;
-use proc_macro2::TokenStream as TokenStream2;
-use proc_macro_error::{abort, ResultExt};
-use quote::quote;
-use single::Single;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
 use syn::{
-    spanned::Spanned, AngleBracketedGenericArguments, DataStruct, Field, GenericArgument, Ident,
-    PathArguments, PathSegment, Type, TypePath,
+    ext::IdentExt, parse_quote, spanned::Spanned, AngleBracketedGenericArguments, Attribute, Field,
+    Fields, Ident, Lit, Meta, MetaNameValue, PathArguments, PathSegment, TraitBound, Type,
+    TypeImplTrait, TypeParamBound, Visibility,
 };
 
 /// A description of how this field should be handled when generating `new`
@@ -14,27 +18,98 @@ use syn::{
 pub struct FieldConfig {
     /// Argument type in `new`
     input_type: Type,
+    /// Argument type as a user would write it, for docs
+    input_doc: String,
     /// Argument name in `new`
     input_name: Ident,
     /// Name of this field in the struct.
     /// None for tuple structs
     struct_name: Option<Ident>,
-    /// Transform to apply in body of `new`
-    transform: TokenStream2,
+    /// Type of this field in the struct
+    field_type: Type,
+    /// Transform to apply in body of `new`.
+    /// None if the argument is already the field type
+    transform: Option<TokenStream2>,
+    /// If set, this field isn't an argument to `new`, and is initialised with this instead
+    default: Option<FieldDefault>,
+    /// `transform` returns a `Result`
+    fallible: bool,
+    /// The field's doc comment, one entry per line
+    docs: Vec<String>,
+    /// The field is parsed, and may fail with the container's parse error
+    parsed: bool,
 }
 
 impl FieldConfig {
-    /// Argument to `new`
-    pub fn input(&self) -> TokenStream2 {
+    /// Argument to `new`, if this field isn't defaulted
+    pub fn input(&self) -> Option<TokenStream2> {
         let input_name = self.input_name.clone();
         let input_type = self.input_type.clone();
-        quote!(#input_name: #input_type)
+        match self.default {
+            Some(_) => None,
+            None => Some(quote!(#input_name: #input_type)),
+        }
     }
     /// Body inside `new`
     pub fn transform(&self) -> TokenStream2 {
         let input_name = self.input_name.clone();
-        let transform = self.transform.clone();
-        quote!(let #input_name = (#transform)(#input_name))
+        match &self.default {
+            Some(default) => quote!(let #input_name = #default),
+            None => self.convert(),
+        }
+    }
+    /// Convert the argument to the field type, ignoring any default
+    fn convert(&self) -> TokenStream2 {
+        let input_name = self.input_name.clone();
+        match (&self.transform, self.fallible) {
+            (None, _) => quote!(let #input_name = #input_name),
+            (Some(transform), true) => quote!(let #input_name = (#transform)(#input_name)?),
+            (Some(transform), false) => quote!(let #input_name = (#transform)(#input_name)),
+        }
+    }
+    /// Whether the constructor must return a `Result`
+    pub fn fallible(&self) -> bool {
+        self.default.is_none() && self.fallible
+    }
+    /// This field, but not an argument to `new`.
+    /// Any default the user has given is kept, else it's `Default::default()`
+    pub fn defaulted(&self) -> Self {
+        FieldConfig {
+            default: Some(self.default.clone().unwrap_or(FieldDefault::Trait)),
+            ..self.clone()
+        }
+    }
+    /// This field, as an argument to `new` even if it has a default
+    pub fn argument(&self) -> Self {
+        FieldConfig {
+            default: None,
+            ..self.clone()
+        }
+    }
+    /// Whether this field is parsed, so the container's parse error is needed
+    pub fn parsed(&self) -> bool {
+        self.parsed
+    }
+    /// Whether `new` can be a `const fn`. Only a `default = ...` value might be,
+    /// so `Default::default()` isn't
+    pub fn is_const(&self) -> bool {
+        match self.default {
+            Some(FieldDefault::Value(_)) => true,
+            Some(FieldDefault::Trait) => false,
+            None => self.transform.is_none() && !self.fallible,
+        }
+    }
+    /// Whether this field is initialised with `Default::default()`
+    pub fn is_default_trait(&self) -> bool {
+        matches!(self.default, Some(FieldDefault::Trait))
+    }
+    /// Argument name in `new`, and the setter's name in a builder
+    pub fn input_name(&self) -> &Ident {
+        &self.input_name
+    }
+    /// Where to report errors about this field
+    pub fn span(&self) -> Span {
+        self.input_name.span()
     }
     /// Argument to constructor
     pub fn output(&self) -> TokenStream2 {
@@ -44,177 +119,256 @@ impl FieldConfig {
             None => quote!(#input_name),
         }
     }
-}
-
-fn magic_field_config(field: Field, input_name: Ident) -> Option<FieldConfig> {
-    match field.ty {
-        Type::Path(TypePath {
-            qself: None,
-            path:
-                syn::Path {
-                    leading_colon: None,
-                    segments,
-                },
-        }) => match segments.into_iter().collect::<Vec<_>>().as_slice() {
-            // String -> impl AsRef<str>
-            [PathSegment {
-                ident,
-                arguments: PathArguments::None,
-            }] if ident.to_string() == "String" => Some(FieldConfig {
-                input_type: syn::parse2(quote!(impl ::std::convert::AsRef<::std::primitive::str>))
-                    .unwrap(),
-                input_name,
-                struct_name: field.ident,
-                transform: quote!(|s| ::std::string::String::from(::std::convert::AsRef::<
-                    ::std::primitive::str,
-                >::as_ref(&s))),
-            }),
-            // Vec<T> -> impl IntoIterator<Item = ...>
-            [PathSegment {
-                ident,
-                arguments:
-                    PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }),
-            }] if ident.to_string() == "Vec" => {
-                match args.into_iter().collect::<Vec<_>>().as_slice() {
-                    // Vec<String> -> impl IntoIterator<Item = impl AsRef<str>>
-                    [GenericArgument::Type(Type::Path(TypePath {
-                        qself: None,
-                        path:
-                            syn::Path {
-                                leading_colon: None,
-                                segments,
-                            },
-                    }))] if simple_segment(segments, "String") => Some(FieldConfig {
-                        input_type: syn::parse2(quote!(
-                            impl ::std::iter::IntoIterator<Item = impl AsRef<::std::primitive::str>>
-                        ))
-                        .unwrap(),
-                        input_name,
-                        struct_name: field.ident,
-                        transform: quote!(|i| {
-                            let mut v = std::vec::Vec::new();
-                            for item in i {
-                                v.push(::std::string::String::from(::std::convert::AsRef::<
-                                    ::std::primitive::str,
-                                >::as_ref(
-                                    &item
-                                )))
-                            }
-                            v
-                        }),
-                    }),
-                    // Vec<PathBuf> -> impl IntoIterator<Item = impl AsRef<Path>>
-                    [GenericArgument::Type(Type::Path(TypePath {
-                        qself: None,
-                        path:
-                            syn::Path {
-                                leading_colon: None,
-                                segments,
-                            },
-                    }))] if simple_segment(segments, "PathBuf") => Some(FieldConfig {
-                        input_type: syn::parse2(quote!(
-                            impl ::std::iter::IntoIterator<Item = impl AsRef<::std::path::Path>>
-                        ))
-                        .unwrap(),
-                        input_name,
-                        struct_name: field.ident,
-                        transform: quote!(|i| {
-                            let mut v = std::vec::Vec::new();
-                            for item in i {
-                                v.push(::std::path::PathBuf::from(::std::convert::AsRef::<
-                                    ::std::path::Path,
-                                >::as_ref(
-                                    &item
-                                )))
-                            }
-                            v
-                        }),
-                    }),
-                    // Vec<T> -> impl IntoIterator<Item = T>
-                    [GenericArgument::Type(ty)] => Some(FieldConfig {
-                        input_type: syn::parse2(quote!(impl ::std::iter::IntoIterator<Item = #ty>))
-                            .unwrap(),
-                        input_name,
-                        struct_name: field.ident,
-                        transform: quote!(|i| {
-                            let mut v = std::vec::Vec::new();
-                            for item in i {
-                                v.push(item)
-                            }
-                            v
-                        }),
-                    }),
-                    _ => None,
+    /// Line in the constructor's docs for this argument, if it is one
+    pub fn argument_doc(&self) -> Option<String> {
+        if self.default.is_some() {
+            return None;
+        }
+        let conversion = match (&self.transform, self.fallible) {
+            (None, _) => String::new(),
+            (Some(_), false) => format!(", converted into `{}`", type_doc(&self.field_type)),
+            (Some(_), true) => format!(
+                ", converted into `{}`, which may fail",
+                type_doc(&self.field_type)
+            ),
+        };
+        Some(format!(
+            "* `{}`: `{}`{}{}",
+            self.input_name.unraw(),
+            self.input_doc,
+            conversion,
+            self.docs
+                .iter()
+                .map(|line| format!("\n  {line}"))
+                .collect::<String>()
+        ))
+    }
+    /// Line in the constructor's docs for this field, if it's defaulted
+    pub fn default_doc(&self) -> Option<String> {
+        let default = match self.default.as_ref()? {
+            FieldDefault::Trait => String::from("Default::default()"),
+            FieldDefault::Value(expr) => expr.to_token_stream().to_string(),
+        };
+        Some(format!("* `{}`: `{}`", self.input_name.unraw(), default))
+    }
+    /// Field in the builder struct
+    pub fn builder_field(&self) -> TokenStream2 {
+        let input_name = self.input_name.clone();
+        let field_type = self.field_type.clone();
+        quote!(#input_name: ::std::option::Option<#field_type>)
+    }
+    /// Initial value of the field in the builder struct
+    pub fn builder_init(&self) -> TokenStream2 {
+        let input_name = self.input_name.clone();
+        quote!(#input_name: ::std::option::Option::None)
+    }
+    /// Method on the builder, which accepts the same argument as `new`
+    pub fn setter(&self, vis: &TokenStream2, error: &TokenStream2) -> TokenStream2 {
+        let input_name = self.input_name.clone();
+        let input_type = self.input_type.clone();
+        let convert = self.convert();
+        let doc = format!("Set `{}`", input_name.unraw());
+        match self.fallible {
+            true => quote! {
+                #[doc = #doc]
+                #vis fn #input_name(
+                    mut self,
+                    #input_name: #input_type,
+                ) -> ::std::result::Result<Self, #error> {
+                    #convert;
+                    self.#input_name = ::std::option::Option::Some(#input_name);
+                    ::std::result::Result::Ok(self)
+                }
+            },
+            false => quote! {
+                #[doc = #doc]
+                #vis fn #input_name(mut self, #input_name: #input_type) -> Self {
+                    #convert;
+                    self.#input_name = ::std::option::Option::Some(#input_name);
+                    self
                 }
+            },
+        }
+    }
+    /// Body inside `build`, which takes the value from the builder, falling back to the default
+    pub fn build(&self) -> TokenStream2 {
+        let input_name = self.input_name.clone();
+        let fallback = match &self.default {
+            Some(default) => quote!(#default),
+            None => {
+                let message = format!("missing field `{}`", input_name.unraw());
+                quote!(return ::std::result::Result::Err(::std::convert::From::from(#message)))
             }
-            // PathBuf -> impl AsRef<Path>
-            [PathSegment { ident, .. }] if ident.to_string() == "PathBuf" => Some(FieldConfig {
-                input_type: syn::parse2(quote!(impl ::std::convert::AsRef<::std::path::Path>))
-                    .unwrap(),
-                input_name,
-                struct_name: field.ident,
-                transform: quote!(|s| ::std::path::PathBuf::from(::std::convert::AsRef::<
-                    ::std::path::Path,
-                >::as_ref(&s))),
-            }),
+        };
+        quote! {
+            let #input_name = match self.#input_name {
+                ::std::option::Option::Some(#input_name) => #input_name,
+                ::std::option::Option::None => #fallback,
+            }
+        }
+    }
+}
+
+fn magic_field_config(field: Field, input_name: Ident, magic: Magic) -> FieldConfig {
+    let Magic {
+        input_type,
+        input_doc,
+        transform,
+    } = magic;
+    FieldConfig {
+        input_type,
+        input_doc,
+        input_name,
+        struct_name: field.ident,
+        field_type: field.ty,
+        transform: Some(transform),
+        default: None,
+        fallible: false,
+        docs: Vec::new(),
+        parsed: false,
+    }
+}
+
+/// The lines of the doc comment in `attrs`
+fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(doc), ..
+            })) => Some(doc.value().trim().to_string()),
             _ => None,
-        },
-        _ => None,
+        })
+        .collect()
+}
+
+/// Accept `impl AsRef<str>`, and parse it with `FromStr`.
+/// Failures are wrapped in `parse_error`, which names the field, or its index in a tuple struct
+fn parse_field_config(
+    field: Field,
+    n: usize,
+    input_name: Ident,
+    parse_error: &Ident,
+) -> FieldConfig {
+    let field_type = field.ty.clone();
+    let name = match &field.ident {
+        Some(ident) => ident.unraw().to_string(),
+        None => n.to_string(),
+    };
+    let transform = quote! {
+        |s| ::std::result::Result::map_err(
+            <#field_type as ::std::str::FromStr>::from_str(
+                ::std::convert::AsRef::<::std::primitive::str>::as_ref(&s)
+            ),
+            |e| #parse_error {
+                field: #name,
+                source: ::std::convert::From::from(e),
+            },
+        )
+    };
+    FieldConfig {
+        input_type: parse_quote!(impl ::std::convert::AsRef<::std::primitive::str>),
+        input_doc: String::from("impl AsRef<str>"),
+        input_name,
+        struct_name: field.ident,
+        field_type: field.ty,
+        transform: Some(transform),
+        default: None,
+        fallible: true,
+        docs: Vec::new(),
+        parsed: true,
     }
 }
 
-fn simple_segment<'a>(
-    segments: impl IntoIterator<Item = &'a PathSegment>,
-    expected: impl AsRef<str>,
-) -> bool {
-    match segments.into_iter().collect::<Vec<_>>().as_slice() {
-        [PathSegment {
+/// `#ident`, the error for fields which couldn't be parsed, naming the field
+pub fn parse_error_type(ident: &Ident, vis: &Visibility, subject: &str) -> TokenStream2 {
+    let doc = format!("A field of {subject} couldn't be parsed");
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug)]
+        #vis struct #ident {
+            /// The field which couldn't be parsed, or its index in a tuple
+            pub field: &'static str,
+            /// Why it couldn't be parsed
+            pub source: ::std::boxed::Box<
+                dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync
+            >,
+        }
+
+        impl ::std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::write!(f, "couldn't parse `{}`: {}", self.field, self.source)
+            }
+        }
+
+        impl ::std::error::Error for #ident {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                ::std::option::Option::Some(&*self.source)
+            }
+        }
+    }
+}
+
+/// How to turn an argument of `input_type` into `field_type`.
+/// `impl AsRef<U>` uses `From<&U>`, `impl IntoIterator` is collected, and anything else uses `Into`
+fn infer_converter(input_type: &Type, field_type: &Type) -> TokenStream2 {
+    let bound = match input_type {
+        Type::ImplTrait(TypeImplTrait { bounds, .. }) => {
+            bounds.iter().find_map(|bound| match bound {
+                TypeParamBound::Trait(TraitBound { path, .. }) => path.segments.last(),
+                _ => None,
+            })
+        }
+        _ => None,
+    };
+    match bound {
+        Some(PathSegment {
             ident,
-            arguments: PathArguments::None,
-        }] if ident.to_string() == expected.as_ref() => true,
-        _ => false,
+            arguments: PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }),
+        }) if ident == "AsRef" => quote! {
+            |t| <#field_type as ::std::convert::From<&#args>>::from(
+                ::std::convert::AsRef::<#args>::as_ref(&t)
+            )
+        },
+        Some(PathSegment { ident, .. }) if ident == "IntoIterator" => quote! {
+            |t| <#field_type as ::std::iter::FromIterator<_>>::from_iter(t)
+        },
+        _ => quote!(|t| ::std::convert::Into::<#field_type>::into(t)),
     }
 }
 
-pub fn make_field_configs(data_struct: &DataStruct) -> Vec<FieldConfig> {
-    data_struct
-        .fields
+/// How to handle each of `fields`. Parsed fields fail with `parse_error`
+pub fn make_field_configs(
+    fields: &Fields,
+    container_config: &ContainerConfig,
+    parse_error: &Ident,
+) -> Vec<FieldConfig> {
+    fields
         .clone()
         .into_iter()
         .enumerate()
         .map(|(n, field)| {
             // Get the #[generic_new(...)], if there is one
-            let generic_new_attribute = match field
-                .attrs
-                .iter()
-                .filter(|attr| {
-                    attr.path
-                        .segments
-                        .first()
-                        .map(|segment| segment.ident.to_string().as_str() == "generic_new")
-                        .unwrap_or(false)
-                })
-                .single()
-            {
-                Ok(a) => Some(a),
-                Err(e) => match e {
-                    single::Error::NoElements => None,
-                    single::Error::MultipleElements => {
-                        abort!(field.span(), "Can't specify `generic_new` more than once")
-                    }
-                },
-            };
+            let generic_new_attribute = generic_new_attribute(&field.attrs, &field);
 
             debug!("{generic_new_attribute:?}");
 
-            // Turn it into UserConfig
+            // Turn it into UserConfig, leaving the field as-is if we can't
             let user_config = generic_new_attribute.map(|attribute| {
                 attribute
                     .parse_args::<UserConfig>()
-                    .expect_or_abort("Couldn't parse attributes")
+                    .unwrap_or_else(|error| {
+                        emit_parse_error(error);
+                        UserConfig {
+                            conversion: Conversion::Ignore,
+                            default: None,
+                        }
+                    })
             });
 
             let span = field.span();
+            let docs = docs(&field.attrs);
 
             let struct_name = field.clone().ident;
             let input_name = field
@@ -223,23 +377,93 @@ pub fn make_field_configs(data_struct: &DataStruct) -> Vec<FieldConfig> {
                 .unwrap_or_else(|| Ident::new(&format!("arg{n}"), span));
 
             let noop_config = FieldConfig {
-                input_type: field.clone().ty,
+                input_type: field.ty.clone(),
+                input_doc: type_doc(&field.ty),
                 input_name: input_name.clone(),
                 struct_name: struct_name.clone(),
-                transform: quote!(|i| i),
+                field_type: field.ty.clone(),
+                transform: None,
+                default: None,
+                fallible: false,
+                docs: Vec::new(),
+                parsed: false,
             };
 
-            match user_config {
+            let (conversion, default) = match user_config {
+                Some(UserConfig {
+                    conversion,
+                    default,
+                }) => (conversion, default),
+                None => (Conversion::Magic, None),
+            };
+
+            let field_config = match conversion {
                 // User has explicitly asked us to ignore this type, so leave as-is
-                Some(UserConfig::Ignore) => noop_config,
+                Conversion::Ignore => noop_config,
                 // User has provided their own conversion
-                Some(UserConfig::Custom(ty, conv)) => FieldConfig {
-                    input_type: ty,
+                Conversion::Custom(ty, conv) => FieldConfig {
+                    input_doc: type_doc(&ty),
+                    input_type: *ty,
+                    input_name,
+                    struct_name,
+                    field_type: field.ty.clone(),
+                    transform: Some(quote!(#conv)),
+                    default: None,
+                    fallible: false,
+                    docs: Vec::new(),
+                    parsed: false,
+                },
+                // User has provided the argument type, so guess the conversion
+                Conversion::InferConverter(ty) => {
+                    let transform = infer_converter(&ty, &field.ty);
+                    FieldConfig {
+                        input_doc: type_doc(&ty),
+                        input_type: *ty,
+                        input_name,
+                        struct_name,
+                        field_type: field.ty.clone(),
+                        transform: Some(transform),
+                        default: None,
+                        fallible: false,
+                        docs: Vec::new(),
+                        parsed: false,
+                    }
+                }
+                // User has provided their own conversion, which may fail
+                Conversion::TryCustom(ty, conv) => FieldConfig {
+                    input_doc: type_doc(&ty),
+                    input_type: *ty,
                     input_name,
                     struct_name,
-                    transform: quote!(#conv),
+                    field_type: field.ty.clone(),
+                    transform: Some(quote!(#conv)),
+                    default: None,
+                    fallible: true,
+                    docs: Vec::new(),
+                    parsed: false,
                 },
-                None => magic_field_config(field, input_name).unwrap_or(noop_config),
+                Conversion::Magic => match classify(&field.ty, &container_config.aliases) {
+                    Some(magic) => magic_field_config(field, input_name, magic),
+                    // User has asked for `impl Into<T>` for all other fields
+                    None if container_config.into => {
+                        let magic = into(&field.ty);
+                        magic_field_config(field, input_name, magic)
+                    }
+                    None => noop_config,
+                },
+                // User has asked for this field to be parsed from a string
+                Conversion::Parse => parse_field_config(field, n, input_name, parse_error),
+                // User has asked for `impl Into<T>` for this field
+                Conversion::Into => {
+                    let magic = into(&field.ty);
+                    magic_field_config(field, input_name, magic)
+                }
+            };
+
+            FieldConfig {
+                default,
+                docs,
+                ..field_config
             }
         })
         .collect()
@@ -10,18 +10,33 @@
 //!
 //! ```rust
 //! # use std::path::PathBuf;
+//! # use std::ffi::OsString;
+//! # use std::sync::{Arc, Mutex};
+//! # use std::collections::HashMap;
 //! use generic_new::GenericNew;
 //!
 //! #[derive(GenericNew)]
 //! struct Foo {
-//!     s: String,      // -> impl AsRef<str>
-//!     v: Vec<usize>,  // -> impl IntoIterator<Item = usize>
-//!     i: Vec<String>, // -> impl IntoIterator<Item = impl AsRef<str>>
-//!     p: PathBuf,     // -> impl AsRef<Path>
+//!     s: String,                 // -> impl AsRef<str>
+//!     v: Vec<usize>,             // -> impl IntoIterator<Item = usize>
+//!     i: Vec<String>,            // -> impl IntoIterator<Item = impl AsRef<str>>
+//!     p: PathBuf,                // -> impl AsRef<Path>
+//!     n: Option<String>,         // -> Option<impl AsRef<str>>
+//!     a: Arc<str>,               // -> impl AsRef<str>, as do Box<str>, Rc<str> and Cow<str>
+//!     m: HashMap<String, usize>, // -> impl IntoIterator<Item = (impl AsRef<str>, usize)>
+//!     os: OsString,              // -> impl AsRef<OsStr>
+//!     b: Vec<u8>,                // -> impl AsRef<[u8]>, as do Box<[u8]>, Rc<[u8]> and Arc<[u8]>
+//!     x: Arc<Mutex<usize>>,      // -> usize, and Box<T>, Rc<T> and Arc<T> take impl Into<...>
+//!     r: Box<[String]>,          // -> impl IntoIterator<Item = impl AsRef<str>>, as do Rc<[T]> and Arc<[T]>
+//!     t: (String, usize),        // -> (impl AsRef<str>, usize), and [String; N] -> [impl AsRef<str>; N]
 //!     #[generic_new(ignore)]
-//!     o: String,      // Turn off magic conversion for some fields
+//!     o: String,                 // Turn off magic conversion for some fields
 //!     #[generic_new(ty = impl Into<usize>, converter = |u|Into::into(u))]
-//!     u: usize,       // Custom converters are supported
+//!     u: usize,                  // Custom converters are supported
+//!     #[generic_new(default)]
+//!     c: Vec<usize>,             // Not an argument, initialised with `Default::default()`
+//!     #[generic_new(default = s.len())]
+//!     l: usize,                  // ...or an expression, which may use earlier arguments
 //! }
 //!
 //! # fn _make_foo() {
@@ -30,21 +45,238 @@
 //!     [1, 2, 3],
 //!     ["a", "b", "c"],
 //!     "path/to/foo",
+//!     Some("name"),
+//!     "arc",
+//!     [("one", 1)],
+//!     "os-string",
+//!     b"bytes",
+//!     0,
+//!     ["x"],
+//!     ("pair", 2),
 //!     String::from("world"),
 //!     1u16,
 //! );
 //!
 //! # }
 //! ```
+//!
+//! Enums get one constructor per variant, named in `snake_case`.
+//! Keywords become raw identifiers like `r#type`, except `super`, `crate` and `self`, which get a trailing underscore.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! enum Shape {
+//!     Circle { radius: f64 },
+//!     NamedRect(String, f64, f64),
+//! }
+//!
+//! Shape::circle(1.0);
+//! Shape::named_rect("label", 1.0, 2.0);
+//! ```
+//!
+//! Converters which may fail are given with `try_converter`, and make the constructor a `try_new`.
+//! Errors are converted with `From` into the error type given at the top level,
+//! or a `Box<dyn Error + Send + Sync>` if there isn't one.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(error = std::num::ParseIntError)]
+//! struct Server {
+//!     host: String,
+//!     #[generic_new(ty = &str, try_converter = str::parse)]
+//!     port: u16,
+//! }
+//!
+//! assert!(Server::try_new("localhost", "8080").is_ok());
+//! assert!(Server::try_new("localhost", "http").is_err());
+//! ```
+//!
+//! `#[generic_new(builder)]` also generates a `FooBuilder`, with a setter per field.
+//! Setters accept the same types as `new`, and fields with a `default` may be left unset.
+//! A missing field fails `build` with the top-level `error`, which must implement `From<&'static str>`.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//! use std::path::PathBuf;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(builder)]
+//! struct Foo {
+//!     name: String,
+//!     paths: Vec<PathBuf>,
+//!     #[generic_new(default = 3)]
+//!     retries: usize,
+//! }
+//!
+//! let foo = Foo::builder()
+//!     .name("hello")
+//!     .paths(["a", "b"])
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(foo.retries, 3);
+//! assert!(Foo::builder().name("hello").build().is_err()); // `paths` is missing
+//! ```
+//!
+//! The constructor's visibility, name and const-ness can be changed at the top level.
+//! A `const` constructor can only take fields as they are, or give them a value with `default = ...`.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(vis = pub(crate), name = create, const)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! const ORIGIN: Point = Point::create(0, 0);
+//! ```
+//!
+//! Several constructors can be declared at the top level, each choosing which fields take their default.
+//! Fields with a `default` of their own take it, unless the constructor lists its `defaults`,
+//! in which case exactly those fields do, so `defaults()` takes every field as an argument.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(
+//!     constructor(name = new),
+//!     constructor(name = with_defaults, defaults(cache, retries)),
+//!     constructor(name = with_retries, defaults(cache)),
+//! )]
+//! struct Client {
+//!     name: String,
+//!     cache: Vec<String>,
+//!     #[generic_new(default = 3)]
+//!     retries: usize,
+//! }
+//!
+//! Client::new("client", ["cached"]);
+//! Client::with_defaults("client");
+//! Client::with_retries("client", 5);
+//! ```
+//!
+//! Magic also applies to std types written with their full path, like `std::string::String`.
+//! Type aliases can be given the same magic as the type they stand for with `alias`.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! type Name = String;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(alias(Name = String))]
+//! struct Person {
+//!     name: Name,
+//!     nicknames: std::vec::Vec<Name>,
+//! }
+//!
+//! Person::new("Ferris", ["crab"]);
+//! ```
+//!
+//! Fields without magic can accept `impl Into<T>` instead, either for the whole type or field by field.
+//! On a field, `into` also replaces its magic, so an `Arc<Mutex<T>>` field can take an existing `Arc`.
+//! It also lets an `Option` with magic take a plain `None`, which otherwise needs a type, like `None::<&str>`.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(into)]
+//! struct Limits {
+//!     min: u64,
+//!     max: u64,
+//!     #[generic_new(ignore)]
+//!     step: u64, // Still takes a `u64`
+//! }
+//!
+//! #[derive(GenericNew)]
+//! struct Timeout {
+//!     #[generic_new(into)]
+//!     millis: u64,
+//!     #[generic_new(into)]
+//!     label: Option<String>,
+//! }
+//!
+//! Limits::new(1u8, 10u32, 1);
+//! Timeout::new(100u16, None);
+//! ```
+//!
+//! Fields can be parsed from strings with `FromStr`, which makes the constructor fallible.
+//! Failures are a generated `<Name>ParseError`, with the `field` that couldn't be parsed, or its index, and the `source` error.
+//! It's returned as it is, unless other fields can fail too, or a custom `error` is given, which is converted from it with `From`.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//! use std::net::IpAddr;
+//!
+//! #[derive(GenericNew)]
+//! struct Server {
+//!     #[generic_new(parse)]
+//!     ip: IpAddr,
+//!     #[generic_new(parse)]
+//!     port: u16,
+//! }
+//!
+//! assert!(Server::try_new("127.0.0.1", "8080").is_ok());
+//! let error = Server::try_new("127.0.0.1", "http").err().unwrap();
+//! assert_eq!(error.to_string(), "couldn't parse `port`: invalid digit found in string");
+//! assert_eq!(error.field, "port");
+//! ```
+//!
+//! `ty` and `converter` can each be left out when the other says enough.
+//! With only `ty`, `impl AsRef<U>` uses `From<&U>`, `impl IntoIterator` is collected, and anything else uses `Into`.
+//! With only `converter`, it must be a closure with a typed parameter.
+//! A path like `converter = str::len` still needs `ty`, since a derive can't see the function's signature.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! struct Foo {
+//!     #[generic_new(ty = impl Into<u64>)]
+//!     id: u64,
+//!     #[generic_new(converter = |s: &str| s.len())]
+//!     len: usize,
+//! }
+//!
+//! Foo::new(1u8, "hello");
+//! ```
+//!
+//! Constructors are documented with their arguments, what each accepts, and which fields are defaulted,
+//! including each field's own doc comment. `doc` replaces this with your own.
+//!
+//! ```rust
+//! use generic_new::GenericNew;
+//!
+//! #[derive(GenericNew)]
+//! #[generic_new(doc = "Make a `Point` from its coordinates")]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//! ```
 
+use attributes::{emit_parse_error, generic_new_attribute};
+use config::{ConstructorConfig, ContainerConfig};
 use field::{make_field_configs, FieldConfig};
 use proc_macro::TokenStream;
-use proc_macro_error::{abort, proc_macro_error};
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_error::{abort, emit_error, proc_macro_error, set_dummy};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, Fields, Ident, Index, Member, Visibility};
 mod attributes;
+mod builder;
 mod config;
+mod docs;
 mod field;
+mod magic;
 
 #[proc_macro_error]
 #[proc_macro_derive(GenericNew, attributes(generic_new))]
@@ -52,38 +284,334 @@ pub fn derive_generic_new(input: TokenStream) -> TokenStream {
     pretty_env_logger::try_init().ok();
     let derive_input = parse_macro_input!(input as DeriveInput);
     let user_ident = derive_input.ident.clone();
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    let container_config = generic_new_attribute(&derive_input.attrs, &derive_input)
+        .and_then(|attribute| {
+            attribute
+                .parse_args::<ContainerConfig>()
+                .map_err(emit_parse_error)
+                .ok()
+        })
+        .unwrap_or_default();
 
-    match derive_input.data {
+    let parse_error = format_ident!("{}ParseError", user_ident);
+    let mut parsed = false;
+    let mut builder = None;
+    let constructors = match derive_input.data {
         syn::Data::Struct(ref user_struct) => {
-            let field_infos = make_field_configs(user_struct);
-            let inputs = field_infos.iter().map(FieldConfig::input);
-            let transforms = field_infos.iter().map(FieldConfig::transform);
-            let outputs = field_infos.iter().map(FieldConfig::output);
-
-            let constructor = match user_struct.fields {
-                syn::Fields::Named(_) => quote!(Self {#(#outputs,)*}),
-                syn::Fields::Unnamed(_) => quote!(Self(#(#outputs,)*)),
-                syn::Fields::Unit => abort!(derive_input, "Unit fields are not supported"),
-            };
-
-            let appended = quote! {
-                impl #user_ident {
-                    pub fn new(
-                        #(#inputs,)*
-                    ) -> Self {
-                        #(#transforms;)*
-                        #constructor
+            let field_infos =
+                make_field_configs(&user_struct.fields, &container_config, &parse_error);
+            let subject = format!("`{user_ident}`");
+            parsed = field_infos.iter().any(FieldConfig::parsed);
+            if container_config.builder {
+                builder = Some(builder::make_builder(
+                    &derive_input,
+                    &user_struct.fields,
+                    &field_infos,
+                    &container_config,
+                    &parse_error,
+                ))
+            }
+            match container_config.constructors.is_empty() {
+                true => vec![make_constructor(
+                    &container_config.constructor(
+                        container_config
+                            .name
+                            .clone()
+                            .unwrap_or_else(|| Ident::new("new", user_ident.span())),
+                    ),
+                    &subject,
+                    quote!(Self),
+                    &user_struct.fields,
+                    &field_infos,
+                    &container_config,
+                    &parse_error,
+                )],
+                false => container_config
+                    .constructors
+                    .iter()
+                    .map(|constructor| {
+                        make_constructor(
+                            constructor,
+                            &subject,
+                            quote!(Self),
+                            &user_struct.fields,
+                            &with_defaults(
+                                &user_struct.fields,
+                                &field_infos,
+                                constructor.defaults.as_deref(),
+                            ),
+                            &container_config,
+                            &parse_error,
+                        )
+                    })
+                    .collect(),
+            }
+        }
+        // One constructor per variant, named after the variant
+        syn::Data::Enum(ref user_enum) => {
+            if container_config.builder {
+                emit_error!(derive_input, "`builder` is not supported on enums")
+            }
+            if let Some(name) = &container_config.name {
+                emit_error!(name, "`name` is not supported on enums")
+            }
+            for constructor in &container_config.constructors {
+                emit_error!(constructor.name, "`constructor` is not supported on enums")
+            }
+            let mut names = Vec::<(Ident, &Ident)>::new();
+            user_enum
+                .variants
+                .iter()
+                .filter_map(|variant| {
+                    let variant_ident = variant.ident.clone();
+                    let name = snake_case(&variant_ident);
+                    if let Some((_, first)) = names.iter().find(|(other, _)| *other == name) {
+                        emit_error!(
+                            variant_ident,
+                            "`{}` and `{}` would both get the constructor `{}`", first, variant_ident, name;
+                            note = first.span() => "`{}` is here", first
+                        );
+                        return None;
                     }
+                    names.push((name.clone(), &variant.ident));
+                    let field_infos =
+                        make_field_configs(&variant.fields, &container_config, &parse_error);
+                    parsed |= field_infos.iter().any(FieldConfig::parsed);
+                    Some(make_constructor(
+                        &container_config.constructor(name),
+                        &format!("`{user_ident}::{variant_ident}`"),
+                        quote!(Self::#variant_ident),
+                        &variant.fields,
+                        &field_infos,
+                        &container_config,
+                        &parse_error,
+                    ))
+                })
+                .collect()
+        }
+        syn::Data::Union(_) => abort!(derive_input, "Unions are not supported"),
+    };
+
+    let parse_error = parsed.then(|| {
+        let vis = match &container_config.vis {
+            Some(vis) => vis,
+            None => &derive_input.vis,
+        };
+        field::parse_error_type(&parse_error, vis, &format!("[`{user_ident}`]"))
+    });
+
+    let appended = quote! {
+        impl #impl_generics #user_ident #ty_generics #where_clause {
+            #(#constructors)*
+        }
+        #builder
+        #parse_error
+    };
+    // Still emit our best effort if there were errors, so they aren't drowned out by missing `new`s
+    set_dummy(appended.clone());
+    appended.into()
+}
+
+/// A `pub fn #name(...) -> Self` which builds `#path` from `fields`.
+/// If there are no fields, or the user asked for it, the function is `const`.
+/// If any of the fields are fallible, this is instead `pub fn try_#name(...) -> Result<Self, E>`,
+/// where `E` is `parse_error` if only parsed fields can fail and the user hasn't given an error
+fn make_constructor(
+    constructor_config: &ConstructorConfig,
+    subject: &str,
+    path: TokenStream2,
+    fields: &Fields,
+    field_infos: &[FieldConfig],
+    container_config: &ContainerConfig,
+    parse_error: &Ident,
+) -> TokenStream2 {
+    let mut constness = constructor_config.constness;
+    if constness {
+        for field_info in field_infos
+            .iter()
+            .filter(|field_info| !field_info.is_const())
+        {
+            match field_info.is_default_trait() {
+                true => emit_error!(
+                    field_info.span(),
+                    "`const` constructors can't call `Default::default()`, so give this field a value with `default = ...`"
+                ),
+                false => emit_error!(
+                    field_info.span(),
+                    "`const` constructors can't convert their arguments, so this field must be `ignore`, or have a value with `default = ...`"
+                ),
+            }
+            constness = false;
+        }
+    }
+    let constness = match field_infos.is_empty() || constness {
+        true => quote!(const),
+        false => quote!(),
+    };
+    let name = &constructor_config.name;
+    let vis = vis(&constructor_config.vis);
+    let inputs = field_infos.iter().filter_map(FieldConfig::input);
+    let transforms = field_infos.iter().map(FieldConfig::transform);
+    let constructor = construct(path, fields, field_infos);
+    let docs = docs::constructor_docs(subject, field_infos, container_config);
+
+    match field_infos.iter().any(FieldConfig::fallible) {
+        true => {
+            let name = format_ident!("try_{}", name);
+            // Only parsed fields can fail, so their error needn't be boxed
+            let parsed_only = field_infos
+                .iter()
+                .filter(|field_info| field_info.fallible())
+                .all(FieldConfig::parsed);
+            let error = error_type(container_config, parsed_only.then_some(parse_error));
+            quote! {
+                #docs
+                #vis fn #name(
+                    #(#inputs,)*
+                ) -> ::std::result::Result<Self, #error> {
+                    #(#transforms;)*
+                    ::std::result::Result::Ok(#constructor)
+                }
+            }
+        }
+        false => quote! {
+            #docs
+            #vis #constness fn #name(
+                #(#inputs,)*
+            ) -> Self {
+                #(#transforms;)*
+                #constructor
+            }
+        },
+    }
+}
+
+/// `field_infos`, with exactly `defaults` taking their default value rather than being arguments.
+/// Without `defaults`, fields keep their own `default`
+fn with_defaults(
+    fields: &Fields,
+    field_infos: &[FieldConfig],
+    defaults: Option<&[Member]>,
+) -> Vec<FieldConfig> {
+    let defaults = match defaults {
+        Some(defaults) => defaults,
+        None => return field_infos.to_vec(),
+    };
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(n, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(n)),
+        })
+        .collect::<Vec<_>>();
+    for unknown in defaults.iter().filter(|default| !members.contains(default)) {
+        emit_error!(unknown, "No field `{}`", quote!(#unknown))
+    }
+    field_infos
+        .iter()
+        .zip(members)
+        .map(|(field_info, member)| match defaults.contains(&member) {
+            true => field_info.defaulted(),
+            false => field_info.argument(),
+        })
+        .collect()
+}
+
+/// Expression which builds `#path`, assuming each field has been bound to its input name
+fn construct(path: TokenStream2, fields: &Fields, field_infos: &[FieldConfig]) -> TokenStream2 {
+    let outputs = field_infos.iter().map(FieldConfig::output);
+    match fields {
+        syn::Fields::Named(_) => quote!(#path {#(#outputs,)*}),
+        syn::Fields::Unnamed(_) => quote!(#path(#(#outputs,)*)),
+        syn::Fields::Unit => quote!(#path),
+    }
+}
+
+/// Visibility of generated functions
+fn vis(vis: &Option<Visibility>) -> TokenStream2 {
+    match vis {
+        Some(vis) => quote!(#vis),
+        None => quote!(pub),
+    }
+}
+
+/// Error type for fallible constructors.
+/// Without one from the user, `parse_error` is used if it's given, else a boxed error
+fn error_type(container_config: &ContainerConfig, parse_error: Option<&Ident>) -> TokenStream2 {
+    match (&container_config.error, parse_error) {
+        (Some(error), _) => quote!(#error),
+        (None, Some(parse_error)) => quote!(#parse_error),
+        (None, None) => default_error_type(),
+    }
+}
+
+/// Error type used when the user hasn't specified one
+fn default_error_type() -> TokenStream2 {
+    quote!(::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>)
+}
+
+/// `NamedRect` -> `named_rect`, `HTTPError` -> `http_error`.
+/// Keywords are made raw, so `Type` -> `r#type`, or suffixed if they can't be, so `Super` -> `super_`
+fn snake_case(ident: &Ident) -> Ident {
+    let chars = ident.to_string().chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).and_then(|i| chars.get(i));
+            let next = chars.get(i + 1);
+            let starts_word = match prev {
+                Some(prev) if *prev != '_' => {
+                    prev.is_lowercase()
+                        || prev.is_numeric()
+                        || next.map(|next| next.is_lowercase()).unwrap_or(false)
                 }
+                _ => false,
             };
-            appended.into()
+            if starts_word {
+                snake.push('_')
+            }
         }
-        syn::Data::Enum(_) => abort!(derive_input, "Enums are not yet supported"),
-        syn::Data::Union(_) => abort!(derive_input, "Unions are not supported"),
+        snake.extend(c.to_lowercase())
+    }
+    match snake.as_str() {
+        // These can't be raw identifiers
+        "self" | "super" | "crate" => Ident::new(&format!("{snake}_"), ident.span()),
+        _ => match syn::parse_str::<Ident>(&snake) {
+            Ok(_) => Ident::new(&snake, ident.span()),
+            Err(_) => Ident::new_raw(&snake, ident.span()),
+        },
     }
 }
+
 #[cfg(test)]
 mod tests {
+    use super::snake_case;
+    use proc_macro2::Span;
+    use syn::Ident;
+
+    #[test]
+    fn snake_case_variants() {
+        for (variant, expected) in [
+            ("Circle", "circle"),
+            ("NamedRect", "named_rect"),
+            ("HTTPError", "http_error"),
+            ("Io2Error", "io2_error"),
+            ("Already_Snake", "already_snake"),
+            ("A", "a"),
+        ] {
+            let snake = snake_case(&Ident::new(variant, Span::call_site()));
+            assert_eq!(snake.to_string(), expected);
+        }
+        let snake = snake_case(&Ident::new("Type", Span::call_site()));
+        assert_eq!(snake.to_string(), "r#type");
+        for (variant, expected) in [("Super", "super_"), ("Crate", "crate_"), ("SELF", "self_")] {
+            let snake = snake_case(&Ident::new(variant, Span::call_site()));
+            assert_eq!(snake.to_string(), expected);
+        }
+    }
+
     #[test]
     fn ui() {
         let t = trybuild::TestCases::new();
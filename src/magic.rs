@@ -0,0 +1,573 @@
+use crate::{attributes::Alias, docs::type_doc};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, AngleBracketedGenericArguments,
+    GenericArgument, Ident, PathArguments, Type, TypeArray, TypePath, TypeSlice, TypeTuple,
+};
+
+/// How to accept an ergonomic argument in place of a type
+#[derive(Debug, Clone)]
+pub struct Magic {
+    /// Argument type
+    pub input_type: Type,
+    /// Argument type as a user would write it, for docs
+    pub input_doc: String,
+    /// Closure which converts the argument to the original type
+    pub transform: TokenStream2,
+}
+
+impl Magic {
+    /// Accept the original type as-is
+    fn identity(ty: &Type) -> Self {
+        Magic {
+            input_type: ty.clone(),
+            input_doc: type_doc(ty),
+            transform: quote!(|t| t),
+        }
+    }
+}
+
+/// If there's a more ergonomic argument type than `ty`, how to accept it.
+/// Containers are classified recursively, so `Vec<Option<String>>` accepts
+/// `impl IntoIterator<Item = Option<impl AsRef<str>>>`.
+/// `aliases` are resolved before classification
+pub fn classify(ty: &Type, aliases: &[Alias]) -> Option<Magic> {
+    if let Some(alias) = aliases
+        .iter()
+        .find(|alias| matches!(std_path(ty), Some((ident, args)) if *ident == alias.name && args.is_empty()))
+    {
+        // Don't resolve this alias again, in case it's recursive
+        let aliases = aliases
+            .iter()
+            .filter(|other| other.name != alias.name)
+            .cloned()
+            .collect::<Vec<_>>();
+        return classify(&alias.ty, &aliases);
+    }
+    match ty {
+        // [T; N] -> [...; N], if there's magic for T
+        Type::Array(TypeArray { elem, len, .. }) => {
+            let Magic {
+                input_type,
+                input_doc,
+                transform,
+            } = classify(elem, aliases)?;
+            return Some(Magic {
+                input_type: parse_quote!([#input_type; #len]),
+                input_doc: format!("[{input_doc}; {}]", len.to_token_stream()),
+                transform: quote!(|a| <[_; #len]>::map(a, #transform)),
+            });
+        }
+        // (T, U) -> (..., ...), if there's magic for any element
+        Type::Tuple(TypeTuple { elems, .. }) => return tuple(elems, aliases),
+        _ => {}
+    }
+    let (ident, args) = std_path(ty)?;
+    match (ident.to_string().as_str(), args.as_slice()) {
+        // String -> impl AsRef<str>
+        ("String", []) => Some(from_str(quote!(::std::string::String::from))),
+        // Box<str> -> impl AsRef<str>
+        ("Box", [GenericArgument::Type(inner)]) if is_str(inner) => Some(from_str(quote!(
+            ::std::boxed::Box::<::std::primitive::str>::from
+        ))),
+        // Rc<str> -> impl AsRef<str>
+        ("Rc", [GenericArgument::Type(inner)]) if is_str(inner) => Some(from_str(quote!(
+            ::std::rc::Rc::<::std::primitive::str>::from
+        ))),
+        // Arc<str> -> impl AsRef<str>
+        ("Arc", [GenericArgument::Type(inner)]) if is_str(inner) => Some(from_str(quote!(
+            ::std::sync::Arc::<::std::primitive::str>::from
+        ))),
+        // Cow<'a, str> -> impl AsRef<str>
+        ("Cow", [GenericArgument::Lifetime(_), GenericArgument::Type(inner)]) if is_str(inner) => {
+            Some(from_str(quote!(|s: &::std::primitive::str| {
+                ::std::borrow::Cow::Owned(::std::string::String::from(s))
+            })))
+        }
+        // PathBuf -> impl AsRef<Path>
+        ("PathBuf", []) => Some(from_ref(
+            quote!(::std::path::Path),
+            "Path",
+            quote!(::std::path::PathBuf::from),
+        )),
+        // OsString -> impl AsRef<OsStr>
+        ("OsString", []) => Some(from_ref(
+            quote!(::std::ffi::OsStr),
+            "OsStr",
+            quote!(::std::ffi::OsString::from),
+        )),
+        // Vec<u8> -> impl AsRef<[u8]>
+        ("Vec", [GenericArgument::Type(inner)]) if is_u8(inner) => Some(from_bytes(quote!(
+            ::std::vec::Vec::<::std::primitive::u8>::from
+        ))),
+        // Box<[u8]> -> impl AsRef<[u8]>, as are Rc<[u8]> and Arc<[u8]>
+        ("Box", [GenericArgument::Type(inner)]) if is_bytes(inner) => Some(from_bytes(quote!(
+            ::std::boxed::Box::<[::std::primitive::u8]>::from
+        ))),
+        ("Rc", [GenericArgument::Type(inner)]) if is_bytes(inner) => Some(from_bytes(quote!(
+            ::std::rc::Rc::<[::std::primitive::u8]>::from
+        ))),
+        ("Arc", [GenericArgument::Type(inner)]) if is_bytes(inner) => Some(from_bytes(quote!(
+            ::std::sync::Arc::<[::std::primitive::u8]>::from
+        ))),
+        // Box<[T]> -> impl IntoIterator<Item = ...>, as do Rc<[T]> and Arc<[T]>
+        ("Box" | "Rc" | "Arc", [GenericArgument::Type(Type::Slice(TypeSlice { elem, .. }))]) => {
+            Some(collect(ty, element(elem, aliases)))
+        }
+        // Box<T> -> impl Into<Box<T>>, as do Rc<T> and Arc<T>,
+        // but Arc<Mutex<T>> and Rc<RefCell<T>> take what the lock takes
+        ("Box", [GenericArgument::Type(inner)]) => Some(pointer(
+            ty,
+            quote!(::std::boxed::Box::new),
+            inner,
+            &[],
+            aliases,
+        )),
+        ("Rc", [GenericArgument::Type(inner)]) => Some(pointer(
+            ty,
+            quote!(::std::rc::Rc::new),
+            inner,
+            &["RefCell"],
+            aliases,
+        )),
+        ("Arc", [GenericArgument::Type(inner)]) => Some(pointer(
+            ty,
+            quote!(::std::sync::Arc::new),
+            inner,
+            &["Mutex", "RwLock"],
+            aliases,
+        )),
+        // Vec<T> -> impl IntoIterator<Item = ...>, and other collections
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [GenericArgument::Type(item), ..]) => {
+            Some(collect(ty, element(item, aliases)))
+        }
+        // HashMap<K, V> -> impl IntoIterator<Item = (..., ...)>, and other maps
+        (
+            "HashMap" | "BTreeMap",
+            [GenericArgument::Type(key), GenericArgument::Type(value), ..],
+        ) => {
+            let Magic {
+                input_type: key_type,
+                input_doc: key_doc,
+                transform: key_transform,
+            } = element(key, aliases);
+            let Magic {
+                input_type: value_type,
+                input_doc: value_doc,
+                transform: value_transform,
+            } = element(value, aliases);
+            Some(collect(
+                ty,
+                Magic {
+                    input_type: parse_quote!((#key_type, #value_type)),
+                    input_doc: format!("({key_doc}, {value_doc})"),
+                    transform: quote!(|(k, v)| ((#key_transform)(k), (#value_transform)(v))),
+                },
+            ))
+        }
+        // Option<T> -> Option<...>, if there's magic for T
+        ("Option", [GenericArgument::Type(inner)]) => {
+            let Magic {
+                input_type,
+                input_doc,
+                transform,
+            } = classify(inner, aliases)?;
+            Some(Magic {
+                input_type: parse_quote!(::std::option::Option<#input_type>),
+                input_doc: format!("Option<{input_doc}>"),
+                transform: quote!(|o| ::std::option::Option::map(o, #transform)),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Accept a tuple of each element's magic
+fn tuple(elems: &Punctuated<Type, Comma>, aliases: &[Alias]) -> Option<Magic> {
+    if !elems.iter().any(|elem| classify(elem, aliases).is_some()) {
+        return None;
+    }
+    let magics = elems
+        .iter()
+        .map(|elem| element(elem, aliases))
+        .collect::<Vec<_>>();
+    let input_types = magics.iter().map(|magic| &magic.input_type);
+    let transforms = magics.iter().map(|magic| &magic.transform);
+    let input_doc = match magics.as_slice() {
+        [single] => format!("({},)", single.input_doc),
+        _ => format!(
+            "({})",
+            magics
+                .iter()
+                .map(|magic| magic.input_doc.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let names = (0..elems.len())
+        .map(|i| format_ident!("t{i}"))
+        .collect::<Vec<_>>();
+    Some(Magic {
+        input_type: parse_quote!((#(#input_types,)*)),
+        input_doc,
+        transform: quote!(|(#(#names,)*)| (#((#transforms)(#names),)*)),
+    })
+}
+
+/// Magic for `ty` if there is some, else accept it as-is
+fn element(ty: &Type, aliases: &[Alias]) -> Magic {
+    classify(ty, aliases).unwrap_or_else(|| Magic::identity(ty))
+}
+
+/// If `inner` is one of `locks`, accept what it holds and wrap it with the lock's `new` and then `new`.
+/// The lock's `new` is called through the field's own path, so locks from other crates work too.
+/// Otherwise `impl Into<ty>`
+fn pointer(ty: &Type, new: TokenStream2, inner: &Type, locks: &[&str], aliases: &[Alias]) -> Magic {
+    match std_path(inner) {
+        Some((lock, args)) if locks.iter().any(|name| lock == name) => match args.as_slice() {
+            [GenericArgument::Type(value)] => {
+                wrap(new, wrap(quote!(<#inner>::new), element(value, aliases)))
+            }
+            _ => into(ty),
+        },
+        _ => into(ty),
+    }
+}
+
+/// `impl Into<ty>`
+pub fn into(ty: &Type) -> Magic {
+    Magic {
+        input_type: parse_quote!(impl ::std::convert::Into<#ty>),
+        input_doc: format!("impl Into<{}>", type_doc(ty)),
+        transform: quote!(|t| ::std::convert::Into::<#ty>::into(t)),
+    }
+}
+
+/// Accept what `inner` accepts, wrapped with `new`
+fn wrap(new: TokenStream2, inner: Magic) -> Magic {
+    let Magic {
+        input_type,
+        input_doc,
+        transform,
+    } = inner;
+    Magic {
+        input_type,
+        input_doc,
+        transform: quote!(|t| (#new)((#transform)(t))),
+    }
+}
+
+/// `impl AsRef<str>`, converted with `from_str`
+fn from_str(from_str: TokenStream2) -> Magic {
+    from_ref(quote!(::std::primitive::str), "str", from_str)
+}
+
+/// `impl AsRef<[u8]>`, converted with `from_bytes`
+fn from_bytes(from_bytes: TokenStream2) -> Magic {
+    from_ref(quote!([::std::primitive::u8]), "[u8]", from_bytes)
+}
+
+/// `impl AsRef<borrowed>`, converted with `from_ref`.
+/// `borrowed_doc` is how a user would write `borrowed`
+fn from_ref(borrowed: TokenStream2, borrowed_doc: &str, from_ref: TokenStream2) -> Magic {
+    Magic {
+        input_type: parse_quote!(impl ::std::convert::AsRef<#borrowed>),
+        input_doc: format!("impl AsRef<{borrowed_doc}>"),
+        transform: quote!(|r| (#from_ref)(::std::convert::AsRef::<#borrowed>::as_ref(&r))),
+    }
+}
+
+/// `impl IntoIterator<Item = ...>`, with each item converted, and collected into `ty`
+fn collect(ty: &Type, item: Magic) -> Magic {
+    let Magic {
+        input_type: item_type,
+        input_doc: item_doc,
+        transform: item_transform,
+    } = item;
+    Magic {
+        input_type: parse_quote!(impl ::std::iter::IntoIterator<Item = #item_type>),
+        input_doc: format!("impl IntoIterator<Item = {item_doc}>"),
+        transform: quote!(|i| <#ty as ::std::iter::FromIterator<_>>::from_iter(
+            ::std::iter::Iterator::map(::std::iter::IntoIterator::into_iter(i), #item_transform)
+        )),
+    }
+}
+
+/// Modules which std types may be named from, as well as being in scope
+const STD_MODULES: &[(&str, &[&str])] = &[
+    ("str", &["std::primitive", "core::primitive"]),
+    ("u8", &["std::primitive", "core::primitive"]),
+    ("String", &["std::string", "alloc::string"]),
+    ("Box", &["std::boxed", "alloc::boxed"]),
+    ("Rc", &["std::rc", "alloc::rc"]),
+    ("Arc", &["std::sync", "alloc::sync"]),
+    ("Cow", &["std::borrow", "alloc::borrow"]),
+    ("Mutex", &["std::sync"]),
+    ("RwLock", &["std::sync"]),
+    ("RefCell", &["std::cell", "core::cell"]),
+    ("PathBuf", &["std::path"]),
+    ("OsString", &["std::ffi"]),
+    ("Option", &["std::option", "core::option"]),
+    ("Vec", &["std::vec", "alloc::vec"]),
+    (
+        "VecDeque",
+        &[
+            "std::collections",
+            "alloc::collections",
+            "std::collections::vec_deque",
+            "alloc::collections::vec_deque",
+        ],
+    ),
+    (
+        "HashSet",
+        &["std::collections", "std::collections::hash_set"],
+    ),
+    (
+        "HashMap",
+        &["std::collections", "std::collections::hash_map"],
+    ),
+    (
+        "BTreeSet",
+        &[
+            "std::collections",
+            "alloc::collections",
+            "std::collections::btree_set",
+            "alloc::collections::btree_set",
+        ],
+    ),
+    (
+        "BTreeMap",
+        &[
+            "std::collections",
+            "alloc::collections",
+            "std::collections::btree_map",
+            "alloc::collections::btree_map",
+        ],
+    ),
+];
+
+/// The name and generic arguments of a type like `Vec<T>` or `std::vec::Vec<T>`
+fn std_path(ty: &Type) -> Option<(&Ident, Vec<&GenericArgument>)> {
+    let path = match ty {
+        Type::Path(TypePath { qself: None, path }) => path,
+        _ => return None,
+    };
+    let segments = path.segments.iter().collect::<Vec<_>>();
+    let (last, module) = segments.split_last()?;
+    let ident = &last.ident;
+    let args = match &last.arguments {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => {
+            args.iter().collect()
+        }
+        PathArguments::Parenthesized(_) => return None,
+    };
+    if module.is_empty() {
+        return match path.leading_colon {
+            Some(_) => None,
+            None => Some((ident, args)),
+        };
+    }
+    if module
+        .iter()
+        .any(|segment| !matches!(segment.arguments, PathArguments::None))
+    {
+        return None;
+    }
+    let module = module
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    STD_MODULES
+        .iter()
+        .any(|(name, modules)| ident == name && modules.contains(&module.as_str()))
+        .then_some((ident, args))
+}
+
+fn is_str(ty: &Type) -> bool {
+    matches!(std_path(ty), Some((ident, args)) if ident == "str" && args.is_empty())
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(std_path(ty), Some((ident, args)) if ident == "u8" && args.is_empty())
+}
+
+/// `[u8]`
+fn is_bytes(ty: &Type) -> bool {
+    matches!(ty, Type::Slice(TypeSlice { elem, .. }) if is_u8(elem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, Magic};
+    use crate::attributes::Alias;
+    use quote::{quote, ToTokens};
+    use syn::Type;
+
+    fn input_type(ty: &str) -> Option<String> {
+        input_type_with_aliases(ty, &[])
+    }
+
+    fn input_type_with_aliases(ty: &str, aliases: &[Alias]) -> Option<String> {
+        let ty = syn::parse_str::<Type>(ty).unwrap();
+        classify(&ty, aliases)
+            .map(|Magic { input_type, .. }| input_type.into_token_stream().to_string())
+    }
+
+    fn expected(ty: &str) -> Option<String> {
+        let ty = syn::parse_str::<Type>(ty).unwrap();
+        Some(ty.into_token_stream().to_string())
+    }
+
+    #[test]
+    fn classify_nested() {
+        assert_eq!(
+            input_type("Vec<Vec<String>>"),
+            expected("impl ::std::iter::IntoIterator<Item = impl ::std::iter::IntoIterator<Item = impl ::std::convert::AsRef<::std::primitive::str>>>")
+        );
+        assert_eq!(
+            input_type("Option<Vec<PathBuf>>"),
+            expected("::std::option::Option<impl ::std::iter::IntoIterator<Item = impl ::std::convert::AsRef<::std::path::Path>>>")
+        );
+        assert_eq!(
+            input_type("Vec<Option<PathBuf>>"),
+            expected("impl ::std::iter::IntoIterator<Item = ::std::option::Option<impl ::std::convert::AsRef<::std::path::Path>>>")
+        );
+        assert_eq!(
+            input_type("Option<Vec<String>>"),
+            expected("::std::option::Option<impl ::std::iter::IntoIterator<Item = impl ::std::convert::AsRef<::std::primitive::str>>>")
+        );
+        assert_eq!(
+            input_type("Vec<OsString>"),
+            expected("impl ::std::iter::IntoIterator<Item = impl ::std::convert::AsRef<::std::ffi::OsStr>>")
+        );
+    }
+
+    #[test]
+    fn classify_passthrough() {
+        assert_eq!(
+            input_type("Vec<usize>"),
+            expected("impl ::std::iter::IntoIterator<Item = usize>")
+        );
+        assert_eq!(input_type("Option<usize>"), None);
+        assert_eq!(input_type("usize"), None);
+    }
+
+    #[test]
+    fn classify_arrays_and_tuples() {
+        assert_eq!(
+            input_type("[String; 2]"),
+            expected("[impl ::std::convert::AsRef<::std::primitive::str>; 2]")
+        );
+        assert_eq!(input_type("[usize; 2]"), None);
+        assert_eq!(
+            input_type("(String, usize, PathBuf)"),
+            expected("(impl ::std::convert::AsRef<::std::primitive::str>, usize, impl ::std::convert::AsRef<::std::path::Path>,)")
+        );
+        assert_eq!(
+            input_type("(String,)"),
+            expected("(impl ::std::convert::AsRef<::std::primitive::str>,)")
+        );
+        assert_eq!(input_type("(usize, bool)"), None);
+        assert_eq!(input_type("()"), None);
+    }
+
+    #[test]
+    fn classify_slices() {
+        assert_eq!(
+            input_type("Box<[usize]>"),
+            expected("impl ::std::iter::IntoIterator<Item = usize>")
+        );
+        assert_eq!(input_type("Box<[String]>"), input_type("Vec<String>"));
+        assert_eq!(
+            input_type("std::sync::Arc<[PathBuf]>"),
+            input_type("Vec<PathBuf>")
+        );
+        assert_eq!(input_type("Rc<[Vec<u8>]>"), input_type("Vec<Vec<u8>>"));
+    }
+
+    #[test]
+    fn classify_pointers() {
+        assert_eq!(
+            input_type("Box<usize>"),
+            expected("impl ::std::convert::Into<Box<usize>>")
+        );
+        assert_eq!(
+            input_type("Box<dyn Error>"),
+            expected("impl ::std::convert::Into<Box<dyn Error>>")
+        );
+        assert_eq!(
+            input_type("Rc<String>"),
+            expected("impl ::std::convert::Into<Rc<String>>")
+        );
+        assert_eq!(input_type("Arc<Mutex<usize>>"), expected("usize"));
+        assert_eq!(input_type("Mutex<usize>"), None);
+        assert_eq!(
+            input_type("Box<RefCell<usize>>"),
+            expected("impl ::std::convert::Into<Box<RefCell<usize>>>")
+        );
+        assert_eq!(
+            input_type("Rc<RefCell<Vec<String>>>"),
+            input_type("Vec<String>")
+        );
+    }
+
+    #[test]
+    fn classify_bytes() {
+        let bytes = expected("impl ::std::convert::AsRef<[::std::primitive::u8]>");
+        assert_eq!(input_type("Vec<u8>"), bytes);
+        assert_eq!(input_type("Box<[u8]>"), bytes);
+        assert_eq!(input_type("std::rc::Rc<[u8]>"), bytes);
+        assert_eq!(input_type("Arc<[std::primitive::u8]>"), bytes);
+        assert_eq!(
+            input_type("Vec<Vec<u8>>"),
+            expected("impl ::std::iter::IntoIterator<Item = impl ::std::convert::AsRef<[::std::primitive::u8]>>")
+        );
+        assert_eq!(
+            input_type("Vec<u16>"),
+            expected("impl ::std::iter::IntoIterator<Item = u16>")
+        );
+    }
+
+    #[test]
+    fn classify_qualified() {
+        assert_eq!(input_type("std::string::String"), input_type("String"));
+        assert_eq!(input_type("::std::path::PathBuf"), input_type("PathBuf"));
+        assert_eq!(input_type("std::ffi::OsString"), input_type("OsString"));
+        assert_eq!(
+            input_type("alloc::vec::Vec<usize>"),
+            input_type("Vec<usize>")
+        );
+        assert_eq!(
+            input_type("std::collections::HashMap<std::string::String, usize>"),
+            input_type("HashMap<String, usize>")
+        );
+        assert_eq!(
+            input_type("std::sync::Arc<std::primitive::str>"),
+            input_type("String")
+        );
+        assert_eq!(input_type("my_crate::String"), None);
+        assert_eq!(input_type("::String"), None);
+        assert_eq!(input_type("std::vec::Vec::<usize>::Foo"), None);
+    }
+
+    #[test]
+    fn classify_aliases() {
+        let aliases = [
+            syn::parse2::<Alias>(quote!(MyString = String)).unwrap(),
+            syn::parse2::<Alias>(quote!(Names = Vec<MyString>)).unwrap(),
+            syn::parse2::<Alias>(quote!(Loop = Loop)).unwrap(),
+        ];
+        assert_eq!(
+            input_type_with_aliases("MyString", &aliases),
+            input_type("String")
+        );
+        assert_eq!(
+            input_type_with_aliases("Names", &aliases),
+            input_type("Vec<String>")
+        );
+        assert_eq!(input_type_with_aliases("Loop", &aliases), None);
+    }
+}
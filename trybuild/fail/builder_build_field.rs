@@ -0,0 +1,11 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(builder)]
+struct Job {
+    name: String,
+    build: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(builder)]
+enum Foo {
+    Bar(String),
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(name = make, constructor(name = new), constructor(name = new))]
+struct Foo {
+    #[generic_new(ty = usize, converter = |u| u, try_converter = |u| Ok(u))]
+    bar: usize,
+    #[generic_new(into, parse)]
+    baz: usize,
+    #[generic_new(converter = |u| u)]
+    qux: usize,
+}
+
+fn main() {}
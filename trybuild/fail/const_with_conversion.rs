@@ -0,0 +1,11 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(const)]
+struct Foo {
+    bar: usize,
+    baz: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(const)]
+struct Foo {
+    #[generic_new(default)]
+    bar: usize,
+    #[generic_new(default = 1)]
+    baz: usize,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(alias(MyString = String, MyString = Box<str>))]
+struct DoubleAlias(MyString);
+
+#[derive(GenericNew)]
+#[generic_new(constructor(defaults(cache)))]
+struct UnnamedConstructor {
+    cache: Vec<String>,
+}
+
+#[derive(GenericNew)]
+#[generic_new(name = create, constructor(name = new))]
+struct NameAndConstructor;
+
+#[derive(GenericNew)]
+#[generic_new(name = create, name = make)]
+struct DoubleName;
+
+fn main() {}
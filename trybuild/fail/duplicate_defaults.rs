@@ -0,0 +1,12 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(constructor(name = new), constructor(name = empty, defaults(cache, retries, cache)))]
+struct Client {
+    name: String,
+    cache: Vec<String>,
+    retries: usize,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(dead_code, non_camel_case_types)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+enum Path {
+    Super,
+    Crate(String),
+    FooBar,
+    Foo_Bar(usize),
+}
+
+fn main() {
+    Path::super_();
+    Path::crate_("name");
+    Path::foo_bar();
+}
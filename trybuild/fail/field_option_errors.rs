@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+struct Foo {
+    #[generic_new()]
+    a: usize,
+    #[generic_new(parse, ty = usize, converter = |u: usize| u)]
+    b: usize,
+    #[generic_new(into, ignore)]
+    c: usize,
+    #[generic_new(default, default = 1)]
+    d: usize,
+    #[generic_new(ty = &'static str, converter = str::len, try_converter = str::parse)]
+    e: usize,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+struct Foo {
+    #[generic_new(ty = usize, ty = usize)]
+    bar: usize,
+    #[generic_new(ignore, ignore)]
+    baz: usize,
+    #[generic_new(converter = |u| u)]
+    qux: usize,
+}
+
+fn main() {
+    Foo::new(1usize, 2usize, 3usize);
+}
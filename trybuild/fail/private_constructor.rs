@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+mod inner {
+    use generic_new::GenericNew;
+
+    #[derive(GenericNew)]
+    #[generic_new(vis = pub(self))]
+    pub struct Foo {
+        bar: usize,
+    }
+}
+
+fn main() {
+    inner::Foo::new(1);
+}
@@ -0,0 +1,11 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(constructor(name = new, defaults(bar, qux)))]
+struct Foo {
+    bar: usize,
+    baz: usize,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(GenericNew)]
+#[generic_new(buidler)]
+struct Foo {
+    #[generic_new(conveter = |u| u)]
+    bar: usize,
+}
+
+#[derive(GenericNew)]
+#[generic_new(default)]
+struct Bar {
+    #[generic_new(builder)]
+    baz: usize,
+}
+
+#[derive(GenericNew)]
+#[generic_new(constructor(nmae = create))]
+struct Baz;
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+fn get_length(s: &str) -> usize {
+    s.len()
+}
+
+#[derive(GenericNew)]
+struct Foo {
+    #[generic_new(converter = |s| s.len())]
+    closure: usize,
+    #[generic_new(converter = get_length)]
+    path: usize,
+}
+
+fn main() {}
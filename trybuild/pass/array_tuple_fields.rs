@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::path::{Path, PathBuf};
+
+#[derive(GenericNew)]
+struct Labelled<const N: usize> {
+    names: [String; N],
+    point: (f64, f64, String),
+    entry: (String, PathBuf),
+    pairs: Vec<(String, usize)>,
+    plain: [usize; 2],
+    unit: (),
+}
+
+fn main() {
+    let labelled = Labelled::new(
+        ["a", "b", "c"],
+        (1.0, 2.0, "origin"),
+        ("key", "/value"),
+        [("one", 1)],
+        [1, 2],
+        (),
+    );
+    assert_eq!(labelled.names, ["a", "b", "c"]);
+    assert_eq!(labelled.point, (1.0, 2.0, String::from("origin")));
+    assert_eq!(labelled.entry.0, "key");
+    assert_eq!(labelled.entry.1, Path::new("/value"));
+    assert_eq!(labelled.pairs, [(String::from("one"), 1)]);
+    assert_eq!(labelled.plain, [1, 2]);
+}
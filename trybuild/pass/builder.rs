@@ -0,0 +1,95 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::HashMap;
+use std::num::ParseIntError;
+use std::path::PathBuf;
+
+#[derive(GenericNew)]
+#[generic_new(builder)]
+pub struct Foo<T: Clone> {
+    name: String,
+    paths: Vec<PathBuf>,
+    ids: Vec<T>,
+    #[generic_new(ignore)]
+    raw: String,
+    #[generic_new(default)]
+    cache: HashMap<String, usize>,
+    #[generic_new(default = name.len())]
+    name_len: usize,
+    #[generic_new(ty = &str, try_converter = str::parse)]
+    port: u16,
+}
+
+#[derive(GenericNew)]
+#[generic_new(builder)]
+pub struct Unit;
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    Missing(&'static str),
+    Port(ParseIntError),
+}
+
+impl From<&'static str> for ConfigError {
+    fn from(message: &'static str) -> Self {
+        ConfigError::Missing(message)
+    }
+}
+
+impl From<ParseIntError> for ConfigError {
+    fn from(e: ParseIntError) -> Self {
+        ConfigError::Port(e)
+    }
+}
+
+#[derive(GenericNew)]
+#[generic_new(builder, error = ConfigError)]
+pub struct Endpoint {
+    host: String,
+    #[generic_new(ty = &str, try_converter = str::parse)]
+    port: u16,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let foo = Foo::builder()
+        .name("hello")
+        .paths(["a", "b"])
+        .ids([1, 2])
+        .raw(String::from("raw"))
+        .port("80")?
+        .build()?;
+    assert_eq!(foo.name_len, 5);
+    assert!(foo.cache.is_empty());
+
+    let foo = Foo::builder()
+        .name("hello")
+        .paths(["a", "b"])
+        .ids([1, 2])
+        .raw(String::from("raw"))
+        .name_len(1)
+        .cache([(String::from("a"), 1)].into_iter().collect::<HashMap<_, _>>())
+        .port("80")?
+        .build()?;
+    assert_eq!(foo.name_len, 1);
+    assert_eq!(foo.cache.len(), 1);
+
+    let missing = Foo::<u8>::builder().name("hello").build();
+    assert_eq!(missing.err().unwrap().to_string(), "missing field `paths`");
+
+    assert!(Foo::<u8>::builder().port("port").is_err());
+
+    Unit::builder().build()?;
+
+    let endpoint = Endpoint::builder()
+        .host("localhost")
+        .port("80")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(endpoint.port, 80);
+    assert_eq!(
+        Endpoint::builder().host("localhost").build().err(),
+        Some(ConfigError::Missing("missing field `port`"))
+    );
+    Ok(())
+}
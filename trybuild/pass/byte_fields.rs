@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(GenericNew)]
+struct Message {
+    payload: Vec<u8>,
+    boxed: Box<[u8]>,
+    rc: Rc<[u8]>,
+    arc: Arc<[u8]>,
+    chunks: Vec<Vec<u8>>,
+    trailer: Option<Vec<u8>>,
+}
+
+fn main() {
+    let message = Message::new(
+        b"abc",
+        vec![1, 2],
+        &[3][..],
+        "text",
+        [b"x", b"y"],
+        Some([0u8; 2]),
+    );
+    assert_eq!(message.payload, b"abc");
+    assert_eq!(&*message.boxed, [1, 2]);
+    assert_eq!(&*message.rc, [3]);
+    assert_eq!(&*message.arc, b"text");
+    assert_eq!(message.chunks, [b"x", b"y"]);
+    assert_eq!(message.trailer.as_deref(), Some(&[0, 0][..]));
+}
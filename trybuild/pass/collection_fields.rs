@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+#[derive(GenericNew)]
+struct Foo<K: std::hash::Hash + Eq> {
+    set: HashSet<usize>,
+    names: BTreeSet<String>,
+    queue: VecDeque<PathBuf>,
+    lookup: HashMap<String, String>,
+    paths: BTreeMap<usize, PathBuf>,
+    generic: HashMap<K, Vec<u8>>,
+}
+
+fn main() {
+    let foo = Foo::new(
+        [1, 2, 2],
+        ["b", "a"],
+        vec![Path::new("a"), Path::new("b")],
+        [("a", "b")],
+        [(1, "one")],
+        [(1u8, vec![1])],
+    );
+    assert_eq!(foo.set.len(), 2);
+    assert_eq!(foo.names.iter().next().unwrap(), "a");
+    assert_eq!(foo.queue.front().unwrap(), Path::new("a"));
+    assert_eq!(foo.lookup["a"], "b");
+    assert_eq!(foo.paths[&1], Path::new("one"));
+    assert_eq!(foo.generic[&1], vec![1]);
+}
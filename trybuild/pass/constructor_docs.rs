@@ -0,0 +1,36 @@
+#![deny(missing_docs)]
+//! Generated constructors are documented
+
+use generic_new::GenericNew;
+
+/// A documented struct
+#[derive(GenericNew)]
+pub struct Foo {
+    /// The name, which is
+    /// documented over two lines
+    pub name: String,
+    /// Parsed from a string
+    #[generic_new(parse)]
+    pub port: u16,
+    /// Defaulted
+    #[generic_new(default)]
+    pub cache: Vec<usize>,
+}
+
+/// A struct with its own constructor docs
+#[derive(GenericNew)]
+#[generic_new(doc = "Make a `Bar`")]
+pub struct Bar(pub usize);
+
+/// A documented enum
+#[derive(GenericNew)]
+pub enum Baz {
+    /// A variant
+    Qux(String),
+}
+
+fn main() {
+    Foo::try_new("foo", "80").unwrap();
+    Bar::new(1);
+    Baz::qux("qux");
+}
@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+mod inner {
+    use generic_new::GenericNew;
+
+    #[derive(GenericNew)]
+    #[generic_new(vis = pub(crate), name = create)]
+    pub struct Foo {
+        pub name: String,
+    }
+
+    #[derive(GenericNew)]
+    #[generic_new(const, vis = pub(crate))]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+        #[generic_new(default = 0)]
+        pub z: i32,
+    }
+
+    #[derive(GenericNew)]
+    #[generic_new(vis = pub(crate), builder)]
+    pub struct Built {
+        pub name: String,
+    }
+
+    #[derive(GenericNew)]
+    #[generic_new(vis = pub(super), name = parse)]
+    pub struct Port(#[generic_new(ty = &str, try_converter = str::parse)] pub u16);
+}
+
+#[derive(GenericNew)]
+#[generic_new(const)]
+enum Direction {
+    Left(usize),
+    Right(#[generic_new(ignore)] String),
+}
+
+const ORIGIN: inner::Point = inner::Point::new(0, 0);
+const LEFT: Direction = Direction::left(1);
+
+fn main() {
+    assert_eq!(inner::Foo::create("hello").name, "hello");
+    assert_eq!(ORIGIN.z, 0);
+    assert_eq!(inner::Built::builder().name("hello").build().unwrap().name, "hello");
+    assert_eq!(inner::Port::try_parse("80").unwrap().0, 80);
+}
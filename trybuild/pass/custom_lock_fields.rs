@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+mod locks {
+    /// Not std's Mutex, and without a `new`
+    pub struct Mutex<T>(pub T);
+}
+
+use locks::Mutex;
+
+#[derive(GenericNew)]
+struct State {
+    name: Mutex<String>,
+    count: std::sync::Mutex<usize>,
+    cell: std::cell::RefCell<bool>,
+}
+
+fn main() {
+    let state = State::new(
+        Mutex(String::from("name")),
+        std::sync::Mutex::new(1),
+        std::cell::RefCell::new(true),
+    );
+    assert_eq!(state.name.0, "name");
+    assert_eq!(*state.count.lock().unwrap(), 1);
+    assert!(*state.cell.borrow());
+}
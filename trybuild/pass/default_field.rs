@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::HashMap;
+
+#[derive(GenericNew)]
+struct Foo {
+    name: String,
+    capacity: usize,
+    #[generic_new(default)]
+    cache: HashMap<String, usize>,
+    #[generic_new(default = Vec::with_capacity(capacity))]
+    buffer: Vec<u8>,
+    #[generic_new(default = name.len())]
+    name_len: usize,
+    retries: Vec<u8>,
+}
+
+#[derive(GenericNew)]
+struct FooTup(
+    String,
+    #[generic_new(default)] Vec<usize>,
+    #[generic_new(ignore, default = String::from("hello"))] String,
+);
+
+#[derive(GenericNew)]
+struct OnlyDefaults {
+    #[generic_new(default)]
+    counter: usize,
+}
+
+fn main() {
+    let foo = Foo::new("name", 8, [1, 2]);
+    assert!(foo.cache.is_empty());
+    assert_eq!(foo.buffer.capacity(), 8);
+    assert_eq!(foo.name_len, 4);
+    let foo_tup = FooTup::new("hello");
+    assert_eq!(foo_tup.2, "hello");
+    assert_eq!(OnlyDefaults::new().counter, 0);
+}
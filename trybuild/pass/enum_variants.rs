@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::path::PathBuf;
+
+#[derive(GenericNew)]
+enum Shape {
+    Circle { radius: f64 },
+    NamedRect(String, f64, f64),
+    Polygon(Vec<(f64, f64)>),
+    Empty,
+}
+
+#[derive(GenericNew)]
+enum Message<T> {
+    Text {
+        body: String,
+        #[generic_new(ignore)]
+        raw: String,
+    },
+    Attachment(PathBuf),
+    Ids(Vec<T>),
+    Type,
+}
+
+fn main() {
+    Shape::circle(1.0);
+    Shape::named_rect("label", 1.0, 2.0);
+    Shape::polygon([(0.0, 0.0), (1.0, 1.0)]);
+    Shape::empty();
+    Message::<u8>::text("hello", String::from("world"));
+    Message::<u8>::attachment("path/to/file");
+    Message::ids([1u8, 2, 3]);
+    Message::<u8>::r#type();
+}
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(GenericNew)]
+struct Foo<'a, T, const N: usize>
+where
+    T: Debug,
+{
+    names: Vec<String>,
+    ids: Vec<T>,
+    borrowed: &'a str,
+    array: [T; N],
+}
+
+#[derive(GenericNew)]
+struct FooTup<T: Clone, A = ()>(Vec<T>, PhantomData<A>);
+
+fn main() {
+    Foo::new(["a", "b"], [1, 2], "borrowed", [1usize, 2, 3]);
+    FooTup::<_>::new([1u8], PhantomData);
+}
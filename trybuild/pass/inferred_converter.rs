@@ -0,0 +1,33 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+#[derive(GenericNew)]
+struct Foo {
+    #[generic_new(ty = impl Into<u64>)]
+    into: u64,
+    #[generic_new(ty = u8)]
+    small: u64,
+    #[generic_new(ty = impl AsRef<str>)]
+    string: String,
+    #[generic_new(ty = impl AsRef<std::path::Path>)]
+    path: PathBuf,
+    #[generic_new(ty = impl IntoIterator<Item = u8>)]
+    set: BTreeSet<u8>,
+    #[generic_new(converter = |s: &str| s.len())]
+    len: usize,
+    #[generic_new(try_converter = |s: &str| s.parse::<u16>())]
+    port: u16,
+}
+
+fn main() {
+    let foo = Foo::try_new(1u32, 2, "string", "path", [3, 3], "len", "80").unwrap();
+    assert_eq!(foo.into, 1);
+    assert_eq!(foo.small, 2);
+    assert_eq!(foo.string, "string");
+    assert_eq!(foo.path, PathBuf::from("path"));
+    assert_eq!(foo.set, BTreeSet::from([3]));
+    assert_eq!(foo.len, 3);
+    assert_eq!(foo.port, 80);
+}
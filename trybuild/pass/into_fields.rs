@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+#[derive(Debug, PartialEq)]
+struct UserId(u64);
+
+impl From<u64> for UserId {
+    fn from(id: u64) -> Self {
+        UserId(id)
+    }
+}
+
+#[derive(GenericNew)]
+#[generic_new(into)]
+struct User {
+    id: UserId,
+    age: u64,
+    name: String,
+    #[generic_new(ignore)]
+    score: u64,
+}
+
+#[derive(GenericNew)]
+struct Limits {
+    #[generic_new(into)]
+    max: u64,
+    min: u64,
+}
+
+fn main() {
+    let user = User::new(1u64, 30u8, "ferris", 10);
+    assert_eq!(user.id, UserId(1));
+    assert_eq!(user.age, 30);
+    assert_eq!(user.name, "ferris");
+    assert_eq!(user.score, 10);
+    let limits = Limits::new(10u32, 1);
+    assert_eq!(limits.max, 10);
+    assert_eq!(limits.min, 1);
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::HashMap;
+
+#[derive(GenericNew)]
+#[generic_new(
+    constructor(name = new),
+    constructor(name = with_defaults, defaults(cache, retries)),
+    constructor(name = named, defaults(retries, timeout), vis = pub(crate)),
+    constructor(name = with_retries, defaults()),
+)]
+pub struct Client {
+    name: String,
+    cache: HashMap<String, String>,
+    #[generic_new(default = 3)]
+    retries: usize,
+    timeout: u64,
+}
+
+#[derive(GenericNew)]
+#[generic_new(const, constructor(name = origin))]
+struct Point(#[generic_new(default = 0)] i32, #[generic_new(default = 0)] i32);
+
+const ORIGIN: Point = Point::origin();
+
+#[derive(GenericNew)]
+#[generic_new(constructor(name = new), constructor(name = on_x_axis, defaults(1)))]
+struct Pair(i32, i32);
+
+fn main() {
+    let client = Client::new("a", HashMap::<String, String>::new(), 10);
+    assert_eq!(client.retries, 3);
+    let client = Client::with_defaults("b", 10);
+    assert!(client.cache.is_empty());
+    assert_eq!(client.retries, 3);
+    let client = Client::named("c", HashMap::<String, String>::new());
+    assert_eq!(client.timeout, 0);
+    let client = Client::with_retries("d", [("k", "v")], 5, 10);
+    assert_eq!(client.retries, 5);
+    assert_eq!(ORIGIN.0, 0);
+    assert_eq!(Pair::new(1, 2).1, 2);
+    assert_eq!(Pair::on_x_axis(1).1, 0);
+}
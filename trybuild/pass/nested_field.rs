@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(GenericNew)]
+struct Foo {
+    table: Vec<Vec<String>>,
+    paths: Vec<Option<PathBuf>>,
+    names: Option<Vec<String>>,
+    groups: HashMap<String, Vec<String>>,
+    maybe: Option<Option<String>>,
+}
+
+fn main() {
+    let foo = Foo::new(
+        [["a", "b"], ["c", "d"]],
+        [Some("a"), None],
+        Some(["x", "y"]),
+        [("group", vec!["a", "b"])],
+        Some(Some("maybe")),
+    );
+    assert_eq!(foo.table, vec![vec!["a", "b"], vec!["c", "d"]]);
+    assert_eq!(foo.paths, vec![Some(PathBuf::from("a")), None]);
+    assert_eq!(foo.names, Some(vec![String::from("x"), String::from("y")]));
+    assert_eq!(foo.groups["group"], vec!["a", "b"]);
+    assert_eq!(foo.maybe, Some(Some(String::from("maybe"))));
+    let _ = Path::new("unused");
+}
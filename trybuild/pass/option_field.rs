@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::path::{Path, PathBuf};
+
+#[derive(GenericNew)]
+struct Foo {
+    name: Option<String>,
+    path: Option<PathBuf>,
+    ids: Option<Vec<usize>>,
+    plain: Option<usize>,
+}
+
+fn main() {
+    let foo = Foo::new(Some("name"), Some(Path::new("path")), Some([1, 2]), None);
+    assert_eq!(foo.name.as_deref(), Some("name"));
+    assert_eq!(foo.ids, Some(vec![1, 2]));
+    let foo = Foo::new(None::<&str>, None::<&Path>, None::<[usize; 0]>, Some(1));
+    assert!(foo.name.is_none());
+    assert!(foo.path.is_none());
+    assert!(foo.ids.is_none());
+}
@@ -0,0 +1,19 @@
+use generic_new::GenericNew;
+use std::path::PathBuf;
+
+#[derive(GenericNew)]
+struct Foo {
+    #[generic_new(into)]
+    name: Option<String>,
+    #[generic_new(into)]
+    path: Option<PathBuf>,
+}
+
+fn main() {
+    let foo = Foo::new(None, None);
+    assert!(foo.name.is_none());
+    assert!(foo.path.is_none());
+    let foo = Foo::new(String::from("name"), Some(PathBuf::from("path")));
+    assert_eq!(foo.name.as_deref(), Some("name"));
+    assert_eq!(foo.path, Some(PathBuf::from("path")));
+}
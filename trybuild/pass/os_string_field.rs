@@ -0,0 +1,22 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+
+#[derive(GenericNew)]
+struct Command {
+    program: OsString,
+    args: Vec<OsString>,
+    arg0: Option<std::ffi::OsString>,
+}
+
+fn main() {
+    let command = Command::new(
+        Path::new("ls"),
+        ["-l", "-a"],
+        Some(OsStr::new("list")),
+    );
+    assert_eq!(command.program, "ls");
+    assert_eq!(command.args, ["-l", "-a"]);
+    assert_eq!(command.arg0.as_deref(), Some(OsStr::new("list")));
+}
@@ -0,0 +1,89 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::error::Error;
+use std::net::{AddrParseError, IpAddr};
+use std::num::ParseIntError;
+
+#[derive(GenericNew)]
+struct Server {
+    name: String,
+    #[generic_new(parse)]
+    ip: IpAddr,
+    #[generic_new(parse)]
+    port: u16,
+}
+
+#[derive(Debug)]
+enum ConfigError {
+    Ip(AddrParseError),
+    Port(ParseIntError),
+    Other(EndpointParseError),
+}
+
+impl From<EndpointParseError> for ConfigError {
+    fn from(e: EndpointParseError) -> Self {
+        match e.field {
+            "ip" => ConfigError::Ip(*e.source.downcast().unwrap()),
+            "port" => ConfigError::Port(*e.source.downcast().unwrap()),
+            _ => ConfigError::Other(e),
+        }
+    }
+}
+
+#[derive(GenericNew)]
+#[generic_new(error = ConfigError)]
+struct Endpoint {
+    #[generic_new(parse)]
+    ip: IpAddr,
+    #[generic_new(parse)]
+    port: u16,
+}
+
+#[derive(GenericNew)]
+struct Pair(#[generic_new(parse)] u8, #[generic_new(parse)] u8);
+
+#[derive(GenericNew)]
+#[generic_new(builder)]
+struct Port {
+    #[generic_new(parse)]
+    number: u16,
+}
+
+#[derive(GenericNew)]
+struct Mixed {
+    #[generic_new(parse)]
+    number: u16,
+    #[generic_new(ty = &str, try_converter = str::parse)]
+    other: u16,
+}
+
+fn main() {
+    let server = Server::try_new("local", "127.0.0.1", String::from("8080")).unwrap();
+    assert_eq!(server.ip, IpAddr::from([127, 0, 0, 1]));
+    assert_eq!(server.port, 8080);
+
+    let error: ServerParseError = Server::try_new("local", "127.0.0.1", "http").err().unwrap();
+    assert!(error.to_string().starts_with("couldn't parse `port`: "));
+    assert_eq!(error.field, "port");
+    assert!(error.source().unwrap().is::<ParseIntError>());
+
+    assert!(Endpoint::try_new("::1", "443").is_ok());
+    assert!(matches!(
+        Endpoint::try_new("nowhere", "443"),
+        Err(ConfigError::Ip(_))
+    ));
+    assert!(matches!(
+        Endpoint::try_new("::1", "-1"),
+        Err(ConfigError::Port(_))
+    ));
+
+    let error = Pair::try_new("1", "x").err().unwrap();
+    assert_eq!(error.field, "1");
+
+    let error: PortParseError = Port::builder().number("x").err().unwrap();
+    assert_eq!(error.field, "number");
+    assert_eq!(Port::builder().number("1").unwrap().build().unwrap().number, 1);
+
+    let error: Box<dyn Error + Send + Sync> = Mixed::try_new("x", "1").err().unwrap();
+    assert_eq!(error.downcast_ref::<MixedParseError>().unwrap().field, "number");
+}
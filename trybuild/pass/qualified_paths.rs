@@ -0,0 +1,36 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+
+type MyString = String;
+type Names = Vec<MyString>;
+
+#[derive(GenericNew)]
+#[generic_new(alias(MyString = String, Names = Vec<MyString>))]
+struct Foo {
+    string: std::string::String,
+    path: ::std::path::PathBuf,
+    map: std::collections::HashMap<std::string::String, usize>,
+    arc: std::sync::Arc<str>,
+    my_string: MyString,
+    names: Names,
+    maybe: Option<MyString>,
+}
+
+fn main() {
+    let foo = Foo::new(
+        "string",
+        "path",
+        [("a", 1)],
+        "arc",
+        "mine",
+        ["x", "y"],
+        Some("maybe"),
+    );
+    assert_eq!(foo.string, "string");
+    assert_eq!(foo.path, std::path::PathBuf::from("path"));
+    assert_eq!(foo.map["a"], 1);
+    assert_eq!(&*foo.arc, "arc");
+    assert_eq!(foo.my_string, "mine");
+    assert_eq!(foo.names, ["x", "y"]);
+    assert_eq!(foo.maybe.as_deref(), Some("maybe"));
+}
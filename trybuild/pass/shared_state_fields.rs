@@ -0,0 +1,42 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+#[derive(GenericNew)]
+struct State {
+    boxed: Box<usize>,
+    error: Box<dyn Error + Send + Sync>,
+    shared: Arc<usize>,
+    counter: Arc<Mutex<usize>>,
+    names: Rc<RefCell<Vec<String>>>,
+    name: Arc<String>,
+    lock: RwLock<bool>,
+    flag: Arc<RwLock<bool>>,
+}
+
+fn main() {
+    let shared = Arc::new(2);
+    let name = Arc::new(String::from("name"));
+    let state = State::new(
+        1,
+        "failed",
+        Arc::clone(&shared),
+        3,
+        ["a", "b"],
+        Arc::clone(&name),
+        RwLock::new(true),
+        false,
+    );
+    assert_eq!(*state.boxed, 1);
+    assert_eq!(state.error.to_string(), "failed");
+    assert!(Arc::ptr_eq(&state.shared, &shared));
+    assert_eq!(*state.counter.lock().unwrap(), 3);
+    assert_eq!(*state.names.borrow(), ["a", "b"]);
+    assert!(Arc::ptr_eq(&state.name, &name));
+    assert_eq!(*State::new(1, "", 2, 3, ["a"], String::from("name"), RwLock::new(true), true).name, "name");
+    assert!(*state.lock.read().unwrap());
+    assert!(!*state.flag.read().unwrap());
+}
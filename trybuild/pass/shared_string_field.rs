@@ -0,0 +1,35 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(GenericNew)]
+struct Foo<'a> {
+    boxed: Box<str>,
+    rc: Rc<str>,
+    arc: Arc<str>,
+    cow: Cow<'a, str>,
+    static_cow: Cow<'static, str>,
+    names: Vec<Arc<str>>,
+    name: Option<Box<str>>,
+}
+
+fn main() {
+    let foo = Foo::new(
+        "boxed",
+        String::from("rc"),
+        "arc",
+        "cow",
+        "static",
+        ["a", "b"],
+        Some("name"),
+    );
+    assert_eq!(&*foo.boxed, "boxed");
+    assert_eq!(&*foo.rc, "rc");
+    assert_eq!(&*foo.arc, "arc");
+    assert_eq!(foo.cow, "cow");
+    assert_eq!(foo.static_cow, "static");
+    assert_eq!(foo.names, vec![Arc::from("a"), Arc::from("b")]);
+    assert_eq!(foo.name.as_deref(), Some("name"));
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(GenericNew)]
+struct Config {
+    ports: Box<[u16]>,
+    names: Box<[String]>,
+    paths: Arc<[PathBuf]>,
+    tags: Rc<[Rc<str>]>,
+}
+
+fn main() {
+    let config = Config::new(vec![80, 443], ["a", "b"], ["/etc", "/usr"], ["tag"]);
+    assert_eq!(&*config.ports, [80, 443]);
+    assert_eq!(&*config.names, ["a", "b"]);
+    assert_eq!(&*config.paths, [Path::new("/etc"), Path::new("/usr")]);
+    assert_eq!(&*config.tags[0], "tag");
+}
@@ -0,0 +1,62 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::num::ParseIntError;
+
+fn parse_port(s: &str) -> Result<u16, ParseIntError> {
+    s.parse()
+}
+
+#[derive(GenericNew)]
+struct Server {
+    host: String,
+    #[generic_new(ty = &str, try_converter = parse_port)]
+    port: u16,
+}
+
+#[derive(Debug)]
+enum ConfigError {
+    Port(ParseIntError),
+    Retries(String),
+}
+
+impl From<ParseIntError> for ConfigError {
+    fn from(e: ParseIntError) -> Self {
+        ConfigError::Port(e)
+    }
+}
+
+impl From<String> for ConfigError {
+    fn from(e: String) -> Self {
+        ConfigError::Retries(e)
+    }
+}
+
+#[derive(GenericNew)]
+#[generic_new(error = ConfigError)]
+struct Config(
+    #[generic_new(ty = &str, try_converter = parse_port)] u16,
+    #[generic_new(ty = usize, try_converter = |u: usize| match u {
+        0..=10 => Ok(u),
+        _ => Err(format!("too many retries: {u}")),
+    })]
+    usize,
+);
+
+#[derive(GenericNew)]
+enum Address {
+    Port(#[generic_new(ty = &str, try_converter = parse_port)] u16),
+    Unix(String),
+}
+
+fn main() {
+    let server = Server::try_new("localhost", "8080").unwrap();
+    assert_eq!(server.port, 8080);
+    assert!(Server::try_new("localhost", "port").is_err());
+
+    assert!(Config::try_new("80", 3).is_ok());
+    assert!(matches!(Config::try_new("port", 3), Err(ConfigError::Port(_))));
+    assert!(matches!(Config::try_new("80", 11), Err(ConfigError::Retries(_))));
+
+    assert!(Address::try_port("22").is_ok());
+    Address::unix("/tmp/socket");
+}
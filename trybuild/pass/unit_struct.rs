@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+use generic_new::GenericNew;
+use std::marker::PhantomData;
+
+#[derive(GenericNew)]
+struct Unit;
+
+#[derive(GenericNew)]
+struct EmptyBraced {}
+
+#[derive(GenericNew)]
+struct EmptyTuple();
+
+#[derive(GenericNew)]
+struct Marker<T>(PhantomData<T>);
+
+#[derive(GenericNew)]
+enum Kind {
+    Plain,
+    Empty {},
+}
+
+const UNIT: Unit = Unit::new();
+const EMPTY_BRACED: EmptyBraced = EmptyBraced::new();
+const EMPTY_TUPLE: EmptyTuple = EmptyTuple::new();
+const PLAIN: Kind = Kind::plain();
+const EMPTY: Kind = Kind::empty();
+
+fn main() {
+    Marker::<u8>::new(PhantomData);
+}